@@ -0,0 +1,23 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use iconcaptcha_solver::IconCaptcha;
+
+fn bench_solve(c: &mut Criterion) {
+    // the 5 icons captcha and the 8 icons one, the matching grows with n²
+    for name in ["icon5-1", "icon8-1"] {
+        let captcha = IconCaptcha::load_image(&format!("captchas/{}.png", name)).unwrap();
+
+        c.bench_function(&format!("{}/positions", name), |b| {
+            b.iter(|| black_box(&captcha).positions())
+        });
+        // positions and cropping of the icons
+        c.bench_function(&format!("{}/icons", name), |b| {
+            b.iter(|| black_box(&captcha).icons())
+        });
+        c.bench_function(&format!("{}/solve", name), |b| {
+            b.iter(|| black_box(&captcha).solve_ref())
+        });
+    }
+}
+
+criterion_group!(benches, bench_solve);
+criterion_main!(benches);
@@ -0,0 +1,11 @@
+#![no_main]
+
+use iconcaptcha_solver::IconCaptcha;
+use libfuzzer_sys::fuzz_target;
+
+// arbitrary bytes through the format guessing and decoding, then the whole solver
+fuzz_target!(|data: &[u8]| {
+    if let Ok(captcha) = IconCaptcha::load_from_bytes(data) {
+        let _ = captcha.solve();
+    }
+});
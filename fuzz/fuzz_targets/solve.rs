@@ -0,0 +1,43 @@
+#![no_main]
+
+use iconcaptcha_solver::{IconCaptcha, Orientation};
+use image::{Rgba, RgbaImage};
+use libfuzzer_sys::fuzz_target;
+
+// the pixels are drawn from the colors the solver tells apart, so the
+// random images are full of delimiters, empty slots and tiny icons
+const PALETTE: [Rgba<u8>; 4] = [
+    Rgba([0, 0, 0, 0]),
+    Rgba([64, 64, 64, 255]),
+    Rgba([255, 255, 255, 255]),
+    Rgba([255, 255, 255, 16]),
+];
+
+fuzz_target!(|data: &[u8]| {
+    let [width, height, flags, rest @ ..] = data else {
+        return;
+    };
+    let (width, height) = (u32::from(width % 128), u32::from(height % 64));
+    let img = RgbaImage::from_fn(width, height, |x, y| {
+        let i = (y * width + x) as usize;
+        let byte = rest.get(i / 4).copied().unwrap_or(0);
+        PALETTE[usize::from(byte >> (i % 4 * 2) & 3)]
+    });
+
+    let mut captcha = IconCaptcha::from_rgba(img);
+    captcha
+        .with_min_length(0)
+        .with_min_slot_width(u32::from(flags & 3))
+        .with_trim_edges(flags & 4 != 0)
+        .with_max_shift(u32::from(flags >> 3 & 1))
+        .with_alpha_threshold(if flags & 16 != 0 { 32 } else { 0 });
+    if flags & 32 != 0 {
+        captcha.with_orientation(Orientation::Vertical);
+    }
+    if flags & 64 != 0 {
+        captcha.with_delimiter_coverage(0.5);
+    }
+    let _ = captcha.positions();
+    let _ = captcha.debug_overlay();
+    let _ = captcha.solve();
+});
@@ -0,0 +1,30 @@
+#ifndef ICONCAPTCHA_SOLVER_H
+#define ICONCAPTCHA_SOLVER_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Solved icon. All fields are 0 when success is false. */
+typedef struct IconCaptchaIcon {
+    uint32_t position;
+    uint32_t start;
+    uint32_t end;
+    uint32_t center_x;
+    uint32_t center_y;
+    bool success;
+} IconCaptchaIcon;
+
+/* Solve a base64 captcha image and write the answer into out.
+ * Returns the same value as out->success, false without writing when out
+ * is NULL. Nothing needs to be freed. */
+bool iconcaptcha_solve_base64(const char *base64, IconCaptchaIcon *out);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,99 @@
+//! C binding, enabled with the `capi` feature.
+//! The declarations for C are in `include/iconcaptcha_solver.h`.
+//! Nothing is allocated across the boundary, the result is written into
+//! a struct owned by the caller.
+
+use std::ffi::{c_char, CStr};
+
+use crate::IconCaptcha;
+
+/// C layout of the solved Icon. All fields are 0 when `success` is false.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IconCaptchaIcon {
+    pub position: u32,
+    pub start: u32,
+    pub end: u32,
+    pub center_x: u32,
+    pub center_y: u32,
+    pub success: bool,
+}
+
+/// Solve a base64 captcha image and write the answer into `out`.
+/// Returns the same value as `out.success`, false without writing when `out` is null.
+///
+/// # Safety
+/// `base64` must be null or a valid nul-terminated string and `out` must be null
+/// or point to writable memory for an `IconCaptchaIcon`.
+#[no_mangle]
+pub unsafe extern "C" fn iconcaptcha_solve_base64(
+    base64: *const c_char,
+    out: *mut IconCaptchaIcon,
+) -> bool {
+    if out.is_null() {
+        return false;
+    }
+    let mut result = IconCaptchaIcon::default();
+    let icon = (!base64.is_null())
+        .then(|| CStr::from_ptr(base64).to_str().ok())
+        .flatten()
+        .and_then(|base64| IconCaptcha::load_from_base64(base64).ok())
+        .and_then(|captcha| captcha.solve().ok());
+    if let Some(icon) = icon {
+        result = IconCaptchaIcon {
+            position: icon.position,
+            start: icon.start,
+            end: icon.end,
+            center_x: icon.center_x,
+            center_y: icon.center_y,
+            success: true,
+        };
+    }
+    out.write(result);
+    result.success
+}
+
+#[cfg(all(test, feature = "capi"))]
+mod test {
+    use std::ffi::CString;
+    use std::ptr;
+
+    use base64::prelude::*;
+
+    use super::*;
+
+    /// An answer left over in `out`, overwritten by every call.
+    const STALE: IconCaptchaIcon = IconCaptchaIcon {
+        position: 9,
+        start: 9,
+        end: 9,
+        center_x: 9,
+        center_y: 9,
+        success: true,
+    };
+
+    #[test]
+    fn solve_base64() {
+        let bytes = std::fs::read("captchas/icon5-1.png").unwrap();
+        let base64 = CString::new(BASE64_STANDARD.encode(bytes)).unwrap();
+        let mut out = STALE;
+        assert!(unsafe { iconcaptcha_solve_base64(base64.as_ptr(), &mut out) });
+        assert!(out.success);
+        assert_eq!(out.position, 3);
+        assert!(out.start <= out.center_x && out.center_x <= out.end);
+
+        assert!(!unsafe { iconcaptcha_solve_base64(base64.as_ptr(), ptr::null_mut()) });
+
+        let mut out = STALE;
+        assert!(!unsafe { iconcaptcha_solve_base64(ptr::null(), &mut out) });
+        assert_eq!(out, IconCaptchaIcon::default());
+
+        let invalid_utf8 = CString::new(vec![0xff, 0xfe]).unwrap();
+        let invalid_base64 = CString::new("not base64!").unwrap();
+        for input in [invalid_utf8, invalid_base64] {
+            let mut out = STALE;
+            assert!(!unsafe { iconcaptcha_solve_base64(input.as_ptr(), &mut out) });
+            assert_eq!(out, IconCaptchaIcon::default());
+        }
+    }
+}
@@ -1,20 +1,162 @@
 //! IconCaptcha Solver is a solution for solving IconCaptcha images.
 //! It provides methods to load images from various sources and extract icons from them.
+//!
+//! # Features
+//!
+//! - `js` (default): the Node.js binding, with neon.
+//! - `python`: the Python binding, with pyo3.
+//! - `wasm`: the WebAssembly binding, with wasm-bindgen.
+//! - `capi`: the C binding, without any dependency.
+//! - `url`: `load_from_url`, with a blocking reqwest client.
+//! - `tokio`: the async loaders, with tokio and reqwest.
+//! - `rayon`: compare the icons in parallel.
+//! - `log`: debug and trace logs of the segmentation and the matching, through
+//!   the `log` facade.
+//! - `serde`: serialize the returned structs.
+//!
+//! Without the default features, the solver only depends on `image` and `base64`:
+//!
+//! ```toml
+//! iconcaptcha-solver = { version = "0.3", default-features = false }
+//! ```
+//!
+//! # Determinism
+//!
+//! Solving the same image with the same settings always gives the same answer, with
+//! or without the `rayon` feature: the parallel results are collected in the order of
+//! the icons, or of the images for the batches, and the ties go to the icon with the
+//! lowest position, unless the tie-break strategy says otherwise.
 
 use base64::prelude::*;
-use image::{DynamicImage, GenericImageView, ImageBuffer, ImageReader, Rgba};
-use std::{fmt::Display, io::Cursor};
+use image::{
+    codecs::{gif::GifDecoder, png::PngDecoder},
+    imageops::FilterType,
+    AnimationDecoder, DynamicImage, GenericImageView, ImageBuffer, ImageFormat, ImageReader, Rgba,
+    RgbaImage,
+};
+use std::{
+    borrow::Cow,
+    error::Error,
+    fmt::Display,
+    io::{BufReader, Cursor, Read, Seek},
+    path::{Path, PathBuf},
+    time::Instant,
+};
 
 #[cfg(feature = "js")]
-use neon::prelude::*;
+use neon::{prelude::*, types::buffer::TypedArray};
 
-#[derive(Debug, Clone)]
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+#[cfg(feature = "python")]
+mod python;
+
+// logs through the `log` facade with the `log` feature, compiled out without it
+macro_rules! debug {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "log")]
+        log::debug!($($arg)*);
+    }};
+}
+
+macro_rules! trace {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "log")]
+        log::trace!($($arg)*);
+    }};
+}
+
+#[cfg(feature = "capi")]
+pub mod capi;
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Icon {
+    /// Position of the icon in the captcha, starting at 1, see `zero_based_position`.
     pub position: u32,
+    /// First column of the icon slot, in full image coordinates.
+    /// First row for vertical captchas.
     pub start: u32,
+    /// Last column of the icon slot, in full image coordinates.
+    /// Last row for vertical captchas.
     pub end: u32,
+    /// Horizontal center of the icon slot, in full image coordinates.
+    /// Horizontal center of the icon for vertical captchas.
     pub center_x: u32,
+    /// Vertical center of the icon, in full image coordinates.
+    /// Vertical center of the icon slot for vertical captchas.
     pub center_y: u32,
+    /// Left column of the cropped icon slot, in full image coordinates.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub slot_x: u32,
+    /// Top row of the cropped icon slot, in full image coordinates.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub slot_y: u32,
+}
+
+impl Icon {
+    /// Center of the icon in full image coordinates, as (x, y).
+    /// This is the point to click on the captcha.
+    pub fn center_abs(&self) -> (u32, u32) {
+        (self.center_abs_x(), self.center_abs_y())
+    }
+
+    /// Point to click on the captcha to select this icon, as (x, y) in pixels of the
+    /// original image: the origin is its top left corner, x grows to the right and
+    /// y downwards. Along the strip it is the middle of the icon slot, across it the
+    /// middle of the icon drawn inside, whatever the orientation of the captcha.
+    /// Scale it when the captcha is displayed at another size than its image.
+    ///
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let icon = IconCaptcha::load_image("captchas/icon5-1.png").unwrap().solve().unwrap();
+    /// assert_eq!(icon.click_point(), (160, 24));
+    /// ```
+    pub fn click_point(&self) -> (u32, u32) {
+        self.center_abs()
+    }
+
+    /// Position of the icon in the captcha starting at 0, to index
+    /// the icons of the page directly. 0 as well for an icon without a position.
+    ///
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let icon = IconCaptcha::load_image("captchas/icon5-1.png").unwrap().solve().unwrap();
+    /// assert_eq!(icon.zero_based_position(), 2);
+    /// ```
+    pub fn zero_based_position(&self) -> usize {
+        (self.position as usize).saturating_sub(1)
+    }
+
+    /// Horizontal center of the icon in full image coordinates.
+    pub fn center_abs_x(&self) -> u32 {
+        self.center_x
+    }
+
+    /// Vertical center of the icon in full image coordinates.
+    pub fn center_abs_y(&self) -> u32 {
+        self.center_y
+    }
+
+    /// Center of the icon relative to the top left corner of its slot, as (x, y).
+    pub fn center_rel(&self) -> (u32, u32) {
+        (self.center_rel_x(), self.center_rel_y())
+    }
+
+    /// Horizontal center of the icon relative to the left column of its slot,
+    /// `slot_x`, whatever the orientation of the captcha.
+    pub fn center_rel_x(&self) -> u32 {
+        self.center_x.saturating_sub(self.slot_x)
+    }
+
+    /// Vertical center of the icon relative to the top row of its slot, `slot_y`:
+    /// the top of the region, or of the rows holding the icons, for horizontal captchas.
+    pub fn center_rel_y(&self) -> u32 {
+        self.center_y.saturating_sub(self.slot_y)
+    }
 }
 
 impl Display for Icon {
@@ -27,293 +169,3028 @@ impl Display for Icon {
     }
 }
 
+impl std::fmt::Debug for Icon {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Icon")
+            .field("position", &self.position)
+            .field("start", &self.start)
+            .field("end", &self.end)
+            .field("center", &(self.center_x, self.center_y))
+            .field("slot", &(self.slot_x, self.slot_y))
+            .finish()
+    }
+}
+
+/// What the solver detected and computed for a captcha, returned by `IconCaptcha::diagnostics`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Diagnostics {
+    /// Delimiters found, as the first and last column of each run of delimiter columns,
+    /// including the edges of the image at 0 and width, or of the region when set.
+    pub delimiters: Vec<(u32, u32)>,
+    /// Number of icons detected.
+    pub icon_count: usize,
+    /// Width and height of the bounding box of each icon.
+    pub icon_sizes: Vec<(u32, u32)>,
+    /// How many other icons each icon matched.
+    pub repeats: Vec<i32>,
+}
+
+/// Time spent in each phase of a solve, returned by `IconCaptcha::solve_with_stats`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SolveStats {
+    /// Nanoseconds spent detecting the delimiters and cropping the icons.
+    pub segmentation_ns: u64,
+    /// Nanoseconds spent comparing the icons with each other.
+    pub matching_ns: u64,
+    /// Number of icon rotations compared, up to the first one matching. The
+    /// rotations of icons of different sizes are counted, but not the pairs of
+    /// icons ruled out beforehand by their moments or histograms.
+    pub comparisons: u64,
+}
+
+/// Errors returned while loading or solving a captcha.
+#[derive(Debug)]
+pub enum IconCaptchaError {
+    /// The input string is not valid base64.
+    Base64Decode(base64::DecodeError),
+    /// The image data could not be decoded.
+    ImageDecode(image::ImageError),
+    /// The data URI is malformed or doesn't hold an image, with the reason.
+    InvalidDataUri(&'static str),
+    /// The image could not be encoded or written.
+    ImageEncode(image::ImageError),
+    /// The image file could not be opened or read.
+    Io(std::io::Error),
+    /// The request for the image failed.
+    #[cfg(any(feature = "url", feature = "tokio"))]
+    Http(reqwest::Error),
+    /// The server answered the image request with a non-success status code.
+    #[cfg(any(feature = "url", feature = "tokio"))]
+    HttpStatus(u16),
+    /// No icons were detected in the captcha image.
+    NoIconsFound,
+    /// No single icon could be told apart from the others.
+    AmbiguousSolution,
+    /// The number of icons detected isn't the one expected.
+    UnexpectedIconCount { expected: usize, found: usize },
+    /// The strip of icons is shorter than the minimum length, as with a truncated download.
+    ImageTooSmall { length: u32, min_length: u32 },
+    /// More icons were detected than the maximum, the image was not solved.
+    TooManyIcons { found: usize, max: usize },
+}
+
+impl Display for IconCaptchaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IconCaptchaError::Base64Decode(err) => write!(f, "invalid base64: {}", err),
+            IconCaptchaError::ImageDecode(err) => write!(f, "invalid image: {}", err),
+            IconCaptchaError::InvalidDataUri(reason) => write!(f, "invalid data URI: {}", reason),
+            IconCaptchaError::ImageEncode(err) => write!(f, "failed to save image: {}", err),
+            IconCaptchaError::Io(err) => write!(f, "io error: {}", err),
+            #[cfg(any(feature = "url", feature = "tokio"))]
+            IconCaptchaError::Http(err) => write!(f, "http error: {}", err),
+            #[cfg(any(feature = "url", feature = "tokio"))]
+            IconCaptchaError::HttpStatus(status) => write!(f, "http status {}", status),
+            IconCaptchaError::NoIconsFound => write!(f, "no icons found in the image"),
+            IconCaptchaError::AmbiguousSolution => {
+                write!(f, "no single icon stands out from the others")
+            }
+            IconCaptchaError::UnexpectedIconCount { expected, found } => {
+                write!(f, "expected {} icons, found {}", expected, found)
+            }
+            IconCaptchaError::ImageTooSmall { length, min_length } => {
+                write!(
+                    f,
+                    "image is {}px long, expected at least {}px",
+                    length, min_length
+                )
+            }
+            IconCaptchaError::TooManyIcons { found, max } => {
+                write!(f, "found {} icons, at most {} are solved", found, max)
+            }
+        }
+    }
+}
+
+impl Error for IconCaptchaError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            IconCaptchaError::Base64Decode(err) => Some(err),
+            IconCaptchaError::ImageDecode(err) => Some(err),
+            IconCaptchaError::ImageEncode(err) => Some(err),
+            IconCaptchaError::Io(err) => Some(err),
+            #[cfg(any(feature = "url", feature = "tokio"))]
+            IconCaptchaError::Http(err) => Some(err),
+            #[cfg(any(feature = "url", feature = "tokio"))]
+            IconCaptchaError::HttpStatus(_) => None,
+            IconCaptchaError::InvalidDataUri(_)
+            | IconCaptchaError::NoIconsFound
+            | IconCaptchaError::AmbiguousSolution
+            | IconCaptchaError::UnexpectedIconCount { .. }
+            | IconCaptchaError::ImageTooSmall { .. }
+            | IconCaptchaError::TooManyIcons { .. } => None,
+        }
+    }
+}
+
+impl IconCaptchaError {
+    /// Name of the error variant, such as `"ImageDecode"`,
+    /// to tell the errors apart across the bindings.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            IconCaptchaError::Base64Decode(_) => "Base64Decode",
+            IconCaptchaError::ImageDecode(_) => "ImageDecode",
+            IconCaptchaError::InvalidDataUri(_) => "InvalidDataUri",
+            IconCaptchaError::ImageEncode(_) => "ImageEncode",
+            IconCaptchaError::Io(_) => "Io",
+            #[cfg(any(feature = "url", feature = "tokio"))]
+            IconCaptchaError::Http(_) => "Http",
+            #[cfg(any(feature = "url", feature = "tokio"))]
+            IconCaptchaError::HttpStatus(_) => "HttpStatus",
+            IconCaptchaError::NoIconsFound => "NoIconsFound",
+            IconCaptchaError::AmbiguousSolution => "AmbiguousSolution",
+            IconCaptchaError::UnexpectedIconCount { .. } => "UnexpectedIconCount",
+            IconCaptchaError::ImageTooSmall { .. } => "ImageTooSmall",
+            IconCaptchaError::TooManyIcons { .. } => "TooManyIcons",
+        }
+    }
+}
+
+impl From<base64::DecodeError> for IconCaptchaError {
+    fn from(err: base64::DecodeError) -> Self {
+        IconCaptchaError::Base64Decode(err)
+    }
+}
+
+#[cfg(any(feature = "url", feature = "tokio"))]
+impl From<reqwest::Error> for IconCaptchaError {
+    fn from(err: reqwest::Error) -> Self {
+        IconCaptchaError::Http(err)
+    }
+}
+
+impl From<std::io::Error> for IconCaptchaError {
+    fn from(err: std::io::Error) -> Self {
+        IconCaptchaError::Io(err)
+    }
+}
+
+impl From<image::ImageError> for IconCaptchaError {
+    fn from(err: image::ImageError) -> Self {
+        IconCaptchaError::ImageDecode(err)
+    }
+}
+
+/// Colors of the separators drawn between the icons by the default IconCaptcha theme.
+const DEFAULT_DELIMITER_COLORS: [[u8; 3]; 2] = [[64, 64, 64], [240, 240, 240]];
+
+/// How the icons are laid out in the captcha.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Orientation {
+    /// Icons from left to right, split by delimiter columns.
+    #[default]
+    Horizontal,
+    /// Icons from top to bottom, split by delimiter rows.
+    Vertical,
+}
+
+/// Channels compared to tell if two icons are the same.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CompareMode {
+    /// Only the alpha channel, for the classic theme with transparent icons.
+    #[default]
+    Alpha,
+    /// All RGBA channels, each one allowed to differ by `tolerance`.
+    Rgba { tolerance: u8 },
+    /// The luminance and the alpha channel, each one allowed to differ by `tolerance`.
+    Luminance { tolerance: u8 },
+}
+
+/// How the solver picks the answer among icons sharing the lowest repeat count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TieBreak {
+    /// The first tied icon in reading order.
+    #[default]
+    First,
+    /// The tied icon whose closest icon among the ones it isn't the same as, over
+    /// every rotation, differs the most, i.e. the most distinct one.
+    MostDistinct,
+    /// Fail with `IconCaptchaError::AmbiguousSolution` on any tie.
+    Error,
+}
+
+/// What a captcha image looks like to the solver, returned by `IconCaptcha::classify`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptchaKind {
+    /// Icons to solve, at least three of them.
+    Normal,
+    /// A blank or single-color image, or one where no icon was found.
+    Empty,
+    /// Every icon matches all the others, no answer can be told apart.
+    AllIdentical,
+    /// One or two icons, too few for one of them to stand out.
+    TooFewIcons,
+}
+
+/// Tells if two icons cropped from a captcha are the same, to find the one icon
+/// that doesn't repeat with `IconCaptcha::solve_with_matcher`.
+pub trait IconMatcher {
+    /// Check if the two icons, trimmed to their bounding box, show the same icon.
+    fn are_same(&self, a: &DynamicImage, b: &DynamicImage) -> bool;
+}
+
+/// The matcher used by `IconCaptcha::solve`: the icons are the same when one
+/// matches a rotation or reflection of the other, comparing their alpha channel
+/// or the channels of the compare mode. Get one with the settings of a captcha
+/// from `IconCaptcha::matcher` or `IconCaptchaBuilder::matcher`, the default one
+/// uses the default settings.
+#[derive(Debug, Clone, Default)]
+pub struct RotationAlphaMatcher {
+    options: Options,
+}
+
+impl IconMatcher for RotationAlphaMatcher {
+    fn are_same(&self, a: &DynamicImage, b: &DynamicImage) -> bool {
+        let options = &self.options;
+        let (a, b) = match options.normalized_size {
+            Some(size) => (Options::normalized(a, size), Options::normalized(b, size)),
+            None => (a.clone(), b.clone()),
+        };
+        let (m1, m2) = (Options::moments(&a), Options::moments(&b));
+        let (h1, h2) = (Options::histogram(&a), Options::histogram(&b));
+        // the rotations of `a` are only compared for interpolated rotations
+        let a_rotated = if options.interpolated() {
+            options.rotate(&a)
+        } else {
+            vec![]
+        };
+        options.moments_close(&a, &m1, &m2)
+            && options.histograms_close(&h1, &h2)
+            && options.pair_match((&a, &a_rotated), (&b, &options.rotate(&b)), &mut 0)
+    }
+}
+
+/// Check if two icons, trimmed to their bounding box, are the same up to a rotation
+/// or a reflection, the comparison `IconCaptcha::solve` makes between the icons with
+/// the default settings. To compare with other settings use `IconCaptcha::matcher`.
+///
+/// Example:
+/// ```
+/// use iconcaptcha_solver::{icons_match, IconCaptcha};
+/// let icons = IconCaptcha::load_image("captchas/icon5-1.png").unwrap().icons();
+/// assert!(icons_match(&icons[0], &icons[0].rotate90()));
+/// assert!(!icons_match(&icons[0], &icons[2]));
+/// ```
+pub fn icons_match(a: &DynamicImage, b: &DynamicImage) -> bool {
+    RotationAlphaMatcher::default().are_same(a, b)
+}
+
+/// Rotation and reflection invariant summary of an icon's alpha channel,
+/// to rule out pairs of icons without comparing their pixels.
+#[derive(Debug, Default)]
+struct Moments {
+    /// Sum of the alpha values.
+    mass: u64,
+    /// Sum of the alpha values weighted by their squared distance to the center.
+    inertia: u64,
+    /// Sum of the squared distances of every pixel to the center.
+    radii: u64,
+    /// Largest squared distance of a pixel to the center.
+    max_radius: u64,
+}
+
+/// Share of the visible pixels of an icon in each color bin,
+/// the channels being quantized to 4 levels.
+type Histogram = [f32; 64];
+
+/// Area around an icon as cropped from the strip by `slot`, with the bounding box
+/// of the icon inside it as (min_x, min_y, max_x, max_y).
+type Slot = (Icon, ImageBuffer<Rgba<u8>, Vec<u8>>, (u32, u32, u32, u32));
+
+/// Strip downscaled to the max width across the rows of `slot_extent`,
+/// with its scale along and across it.
+type ScaledStrip = (DynamicImage, (f32, f32));
+
+/// Largest alpha difference between two pixels still considered equal
+/// when comparing icons rotated with interpolation.
+const ROTATION_ALPHA_TOLERANCE: u8 = 64;
+
+/// Settings used by the solver to segment and compare the icons.
+#[derive(Debug, Clone, PartialEq)]
+struct Options {
+    delimiter_colors: Vec<[u8; 3]>,
+    color_tolerance: u8,
+    delimiter_row: u32,
+    delimiter_coverage: Option<f32>,
+    min_slot_width: u32,
+    min_length: u32,
+    max_icons: usize,
+    trim_edges: bool,
+    region: Option<(u32, u32, u32, u32)>,
+    reflections: bool,
+    rotation_steps: u32,
+    compare_mode: CompareMode,
+    match_threshold: f32,
+    normalized_size: Option<u32>,
+    orientation: Orientation,
+    max_width: Option<u32>,
+    max_shift: u32,
+    tie_break: TieBreak,
+    background: Option<([u8; 3], u8)>,
+    alpha_threshold: u8,
+    histogram_threshold: Option<f32>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            delimiter_colors: DEFAULT_DELIMITER_COLORS.to_vec(),
+            color_tolerance: 0,
+            delimiter_row: 0,
+            delimiter_coverage: None,
+            min_slot_width: 4,
+            min_length: 50,
+            max_icons: 20,
+            trim_edges: false,
+            region: None,
+            reflections: true,
+            rotation_steps: 4,
+            compare_mode: CompareMode::Alpha,
+            match_threshold: 0.0,
+            normalized_size: None,
+            orientation: Orientation::Horizontal,
+            max_width: None,
+            max_shift: 0,
+            tie_break: TieBreak::First,
+            background: None,
+            alpha_threshold: 0,
+            histogram_threshold: None,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
 pub struct IconCaptcha {
     img: DynamicImage,
+    options: Options,
+}
+
+impl std::fmt::Debug for IconCaptcha {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // the pixels are left out, they would flood the output
+        f.debug_struct("IconCaptcha")
+            .field("width", &self.img.width())
+            .field("height", &self.img.height())
+            .field("color", &self.img.color())
+            .field("options", &self.options)
+            .finish()
+    }
 }
 
 impl IconCaptcha {
-    /// Load an image from a file path.
+    fn new(img: DynamicImage) -> Self {
+        // the decoders expand palettes and transparency chunks and hand out straight
+        // alpha, converting every color type to RGBA8 makes the alpha tests behave
+        // the same whatever the encoding of the captcha, and scales 16-bit and
+        // floating point channels down so the delimiter colors stay 8-bit
+        let img = match img {
+            DynamicImage::ImageRgba8(_) => img,
+            img => DynamicImage::ImageRgba8(img.into_rgba8()),
+        };
+        Self {
+            img,
+            options: Options::default(),
+        }
+    }
+
+    /// Use an image already decoded, without encoding it back to bytes for a loader.
     /// Example:
     /// ```
     /// use iconcaptcha_solver::IconCaptcha;
-    /// let captcha = IconCaptcha::load_image("path/to/image.png");
+    /// let img = image::open("captchas/icon5-1.png").unwrap();
+    /// let captcha = IconCaptcha::from_image(img);
     /// ```
-    pub fn load_image(path: &str) -> Self {
-        let img = ImageReader::open(path).unwrap().decode().unwrap();
-        Self { img }
+    pub fn from_image(img: DynamicImage) -> Self {
+        Self::new(img)
     }
 
-    /// Load an image from a base64 string.
+    /// Use an RGBA image buffer, like one composited from layers, as is:
+    /// it is already in the color type the solver works with.
     /// Example:
     /// ```
     /// use iconcaptcha_solver::IconCaptcha;
-    /// let captcha = IconCaptcha::load_from_base64("base64_string");
+    /// let buf = image::open("captchas/icon5-1.png").unwrap().into_rgba8();
+    /// let captcha = IconCaptcha::from_rgba(buf);
+    /// assert_eq!(captcha.solve().unwrap().position, 3);
     /// ```
-    pub fn load_from_base64(base64: &str) -> Result<Self, String> {
-        let base64_dec = BASE64_STANDARD.decode(base64);
-        if let Err(_) = base64_dec {
-            return Err("Invalid base64".to_string());
-        }
-        let img = ImageReader::new(Cursor::new(&base64_dec.unwrap()[..]))
-            .with_guessed_format()
-            .unwrap()
-            .decode();
-        if let Err(_) = img {
-            return Err("Invalid image".to_string());
-        }
+    pub fn from_rgba(buf: RgbaImage) -> Self {
+        Self::new(DynamicImage::ImageRgba8(buf))
+    }
+
+    /// Load an image from a file path.
+    /// Returns `IconCaptchaError::Io` if the file can't be opened and
+    /// `IconCaptchaError::ImageDecode` if its content isn't a valid image.
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// ```
+    pub fn load_image(path: &str) -> Result<Self, IconCaptchaError> {
+        Self::load_path(Path::new(path))
+    }
 
-        Ok(Self { img: img.unwrap() })
+    fn load_path(path: &Path) -> Result<Self, IconCaptchaError> {
+        let img = ImageReader::open(path)?.decode()?;
+        Ok(Self::new(img))
     }
 
-    /// Load an image from a byte array.
+    /// Load and solve every image of a list of paths, with the default settings.
+    /// Each image gets its own result so one bad file doesn't stop the others,
+    /// and the results are in the order of the paths.
+    /// With the `rayon` feature the images are solved in parallel.
+    ///
     /// Example:
     /// ```
     /// use iconcaptcha_solver::IconCaptcha;
-    /// let captcha = IconCaptcha::load_from_bytes(vec![0, 1, 2, 3]);
+    /// let results = IconCaptcha::solve_batch(["captchas/icon5-1.png", "captchas/icon5-2.png"]);
+    /// assert_eq!(results.len(), 2);
     /// ```
-    pub fn load_from_bytes(bytes: Vec<u8>) -> Self {
-        let img = ImageReader::new(Cursor::new(&bytes[..]))
-            .with_guessed_format()
-            .unwrap()
-            .decode()
-            .unwrap();
+    pub fn solve_batch<I: IntoIterator<Item = impl AsRef<Path>>>(
+        paths: I,
+    ) -> Vec<Result<Icon, IconCaptchaError>> {
+        let paths: Vec<PathBuf> = paths
+            .into_iter()
+            .map(|path| path.as_ref().to_path_buf())
+            .collect();
 
-        Self { img }
+        #[cfg(feature = "rayon")]
+        let paths = paths.par_iter();
+        #[cfg(not(feature = "rayon"))]
+        let paths = paths.iter();
+
+        paths.map(|path| Self::load_path(path)?.solve()).collect()
     }
 
-    /// Save the captcha image to a file.
+    /// Decode and solve every image of a list of in-memory buffers, like `solve_batch`.
+    /// The buffers are only borrowed and decoded in place, so memory mapped files
+    /// stay the backing store of the encoded images.
+    ///
     /// Example:
     /// ```
     /// use iconcaptcha_solver::IconCaptcha;
-    /// let captcha = IconCaptcha::load_from_bytes(vec![0, 1, 2, 3]);
-    /// captcha.save("captcha.png");
+    /// let buffers = [
+    ///     std::fs::read("captchas/icon5-1.png").unwrap(),
+    ///     std::fs::read("captchas/icon5-2.png").unwrap(),
+    /// ];
+    /// let results = IconCaptcha::solve_batch_bytes(&buffers);
+    /// assert_eq!(results.len(), 2);
     /// ```
-    pub fn save(&self, path: &str) {
-        self.img.save(path).unwrap()
+    pub fn solve_batch_bytes<B: AsRef<[u8]> + Sync>(
+        buffers: &[B],
+    ) -> Vec<Result<Icon, IconCaptchaError>> {
+        #[cfg(feature = "rayon")]
+        let buffers = buffers.par_iter();
+        #[cfg(not(feature = "rayon"))]
+        let buffers = buffers.iter();
+
+        buffers
+            .map(|bytes| Self::load_from_bytes(bytes.as_ref())?.solve())
+            .collect()
     }
 
-    fn get_positions(&self) -> Vec<Icon> {
-        let img = self.img.clone();
-        let height = img.height();
-        let width = img.width();
+    /// Solve several renderings of the same challenge, like the ones fetched by
+    /// refreshing the captcha, and vote on the answer: the position most of them
+    /// give wins, with the Icon of the first image giving it. The images failing
+    /// to solve don't vote, and when none of them solves the error of the first is
+    /// returned. Fails with `IconCaptchaError::AmbiguousSolution` when several
+    /// positions get the most votes, and `IconCaptchaError::NoIconsFound` without images.
+    /// With the `rayon` feature the images are solved in parallel.
+    ///
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let images = vec![
+    ///     IconCaptcha::load_image("captchas/icon5-1.png").unwrap(),
+    ///     IconCaptcha::load_image("captchas/icon5-1.png").unwrap(),
+    ///     IconCaptcha::load_image("captchas/icon5-2.png").unwrap(),
+    /// ];
+    /// let icon = IconCaptcha::solve_ensemble(images).unwrap();
+    /// assert_eq!(icon.position, 3);
+    /// ```
+    pub fn solve_ensemble(images: Vec<IconCaptcha>) -> Result<Icon, IconCaptchaError> {
+        #[cfg(feature = "rayon")]
+        let images = images.into_par_iter();
+        #[cfg(not(feature = "rayon"))]
+        let images = images.into_iter();
 
-        // array initiate with 0
-        // 0 is position initial position
-        let mut delimiter = vec![0];
+        let results: Vec<Result<Icon, IconCaptchaError>> = images.map(Self::solve).collect();
 
-        for i in 0..width {
-            let pixel = img.get_pixel(i, 0);
-            if pixel[0] == 64 && pixel[1] == 64 && pixel[2] == 64 {
-                delimiter.push(i);
-            }
-            if pixel[0] == 240 && pixel[1] == 240 && pixel[2] == 240 {
-                delimiter.push(i);
+        // votes for each position, in the order the positions were first given
+        let mut votes: Vec<(Icon, usize)> = vec![];
+        for icon in results.iter().flatten() {
+            match votes
+                .iter_mut()
+                .find(|(voted, _)| voted.position == icon.position)
+            {
+                Some((_, count)) => *count += 1,
+                None => votes.push((icon.clone(), 1)),
             }
         }
+        debug!(
+            "votes: {:?}",
+            votes
+                .iter()
+                .map(|(icon, count)| (icon.position, *count))
+                .collect::<Vec<_>>()
+        );
 
-        // width end position
-        let _ = delimiter.push(width);
+        let most = match votes.iter().map(|(_, count)| *count).max() {
+            Some(most) => most,
+            None => {
+                return Err(match results.into_iter().next() {
+                    Some(Err(err)) => err,
+                    _ => IconCaptchaError::NoIconsFound,
+                })
+            }
+        };
+        let mut winners = votes.into_iter().filter(|(_, count)| *count == most);
+        match (winners.next(), winners.next()) {
+            (Some((icon, _)), None) => Ok(icon),
+            _ => Err(IconCaptchaError::AmbiguousSolution),
+        }
+    }
 
-        let mut imgs_positions = vec![];
+    /// Load an image from a base64 string.
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let captcha = IconCaptcha::load_from_base64("base64_string");
+    /// ```
+    pub fn load_from_base64(base64: &str) -> Result<Self, IconCaptchaError> {
+        let base64_dec = BASE64_STANDARD.decode(base64)?;
+        Self::load_from_bytes(&base64_dec)
+    }
 
-        for i in 0..delimiter.len() - 1 {
-            // reverse to avoid negative result
-            // start == initial position or color gray
-            // end == end position or color gray
-            // delimiter[end], delimiter[start]
-            let (p_end, p_start) = (delimiter[i + 1], delimiter[i]);
+    /// Load an image from a data URI, such as `data:image/png;base64,...`.
+    /// The payload may be base64 or percent-encoded, and the media type must be an image.
+    /// Returns `IconCaptchaError::InvalidDataUri` if the URI is malformed or holds
+    /// anything else than an image.
+    /// Example:
+    /// ```
+    /// use base64::prelude::*;
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let bytes = std::fs::read("captchas/icon5-1.png").unwrap();
+    /// let uri = format!("data:image/png;base64,{}", BASE64_STANDARD.encode(bytes));
+    /// let captcha = IconCaptcha::load_from_data_uri(&uri).unwrap();
+    /// ```
+    pub fn load_from_data_uri(uri: &str) -> Result<Self, IconCaptchaError> {
+        let rest = match uri.get(..5) {
+            Some(scheme) if scheme.eq_ignore_ascii_case("data:") => &uri[5..],
+            _ => return Err(IconCaptchaError::InvalidDataUri("missing data: scheme")),
+        };
+        let (header, payload) = rest
+            .split_once(',')
+            .ok_or(IconCaptchaError::InvalidDataUri(
+                "missing comma before the data",
+            ))?;
+        let mut params = header.split(';');
+        let media_type = params.next().unwrap_or_default().trim();
+        if !media_type.to_ascii_lowercase().starts_with("image/") {
+            return Err(IconCaptchaError::InvalidDataUri(
+                "media type is not an image",
+            ));
+        }
+        let base64 = params.any(|param| param.trim().eq_ignore_ascii_case("base64"));
 
-            //calculate center
-            // (p_end - 1) - (p_start + 1) == icon width
-            // (p_end - 1) - (p_start + 1)) / 2) == icon center
-            // (((p_end - 1) - (p_start + 1)) / 2) + delimiter[i] + 1 == icon center position
-            let center = (((p_end - 1) - (p_start + 1)) / 2) + delimiter[i] + 1;
-            //end - start - center
-            imgs_positions.push(vec![p_end - 1, p_start + 1, center]);
+        let data = percent_decode(payload)?;
+        if base64 {
+            // whitespace is allowed in the payload, as in a wrapped attribute
+            let data: Vec<u8> = data
+                .into_iter()
+                .filter(|b| !b.is_ascii_whitespace())
+                .collect();
+            Self::load_from_bytes(&BASE64_STANDARD.decode(data)?)
+        } else {
+            Self::load_from_bytes(&data)
         }
+    }
 
-        let mut icons_positions: Vec<Icon> = Vec::new();
-        for (index, icon) in imgs_positions.iter().enumerate() {
-            let icon = Icon {
-                position: index as u32 + 1,
-                start: icon[1],
-                end: icon[0],
-                center_x: icon[2],
-                center_y: height / 2,
-            };
-            icons_positions.push(icon);
-        }
-        icons_positions
-    }
-
-    fn cropped(&self, icons_positions: &Vec<Icon>) -> Vec<DynamicImage> {
-        let mut icons = vec![];
-        for positions in icons_positions {
-            let img_rgb = self
-                .img
-                .crop_imm(positions.start, 0, positions.end - positions.start, 50)
-                .to_rgba8();
-
-            let (width, height) = img_rgb.dimensions();
-            let mut min_x = width;
-            let mut min_y = height;
-            let mut max_x = 0;
-            let mut max_y = 0;
-
-            // It goes through all the pixels and identifies the corners
-            // of the icon's bounding box.
-            //  (min_x, min_y)
-            //   \
-            //    \
-            //     \
-            //      \
-            //       (max_x, max_y)
-
-            for (x, y, pixel) in img_rgb.enumerate_pixels() {
-                if pixel.0[3] != 0 {
-                    if x < min_x {
-                        min_x = x;
-                    }
-                    if y < min_y {
-                        min_y = y;
-                    }
-                    if x > max_x {
-                        max_x = x;
-                    }
-                    if y > max_y {
-                        max_y = y;
-                    }
+    /// Load an image from a byte array.
+    /// The bytes are decoded in place without being copied, a memory mapped file works too.
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let bytes = std::fs::read("captchas/icon5-1.png").unwrap();
+    /// let captcha = IconCaptcha::load_from_bytes(&bytes).unwrap();
+    /// ```
+    pub fn load_from_bytes(bytes: &[u8]) -> Result<Self, IconCaptchaError> {
+        Self::load_from_reader(Cursor::new(bytes))
+    }
+
+    /// Load every frame of an animated GIF or APNG, the other loaders only decode
+    /// the first frame, which is sometimes blank.
+    /// Still images, in any format, give a single frame.
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let bytes = std::fs::read("captchas/icon5-1.png").unwrap();
+    /// let frames = IconCaptcha::load_frames_from_bytes(&bytes).unwrap();
+    /// // solve the first frame where icons are found
+    /// let icon = frames.into_iter().find_map(|frame| frame.solve().ok());
+    /// ```
+    pub fn load_frames_from_bytes(bytes: &[u8]) -> Result<Vec<Self>, IconCaptchaError> {
+        let frames = match image::guess_format(bytes)? {
+            ImageFormat::Gif => GifDecoder::new(Cursor::new(bytes))?
+                .into_frames()
+                .collect_frames()?,
+            ImageFormat::Png => {
+                let decoder = PngDecoder::new(Cursor::new(bytes))?;
+                if !decoder.is_apng()? {
+                    return Ok(vec![Self::load_from_bytes(bytes)?]);
                 }
+                decoder.apng()?.into_frames().collect_frames()?
             }
+            _ => return Ok(vec![Self::load_from_bytes(bytes)?]),
+        };
+        Ok(frames
+            .into_iter()
+            .map(|frame| Self::new(DynamicImage::ImageRgba8(frame.into_buffer())))
+            .collect())
+    }
+
+    /// Load an image from any reader, such as an open file.
+    /// The format is guessed from the content, like in `load_from_bytes`.
+    /// Fails with `IconCaptchaError::Io` when the reader does.
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let file = std::fs::File::open("captchas/icon5-1.png").unwrap();
+    /// let captcha = IconCaptcha::load_from_reader(file).unwrap();
+    /// ```
+    pub fn load_from_reader<R: Read + Seek>(reader: R) -> Result<Self, IconCaptchaError> {
+        let img = ImageReader::new(BufReader::new(reader))
+            .with_guessed_format()?
+            .decode()?;
+
+        Ok(Self::new(img))
+    }
 
-            // Calculate the dimensions of the new image
-            // resulting in the area + 1 pixel to fit
-            // completely the icon
-            let new_width = max_x - min_x + 1;
-            let new_height = max_y - min_y + 1;
+    /// Load an image from a byte array in the given format, without guessing it
+    /// from the content, for images whose magic bytes were stripped.
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// use image::ImageFormat;
+    /// let bytes = std::fs::read("captchas/icon5-1.png").unwrap();
+    /// let captcha = IconCaptcha::load_from_bytes_with_format(&bytes, ImageFormat::Png).unwrap();
+    /// ```
+    pub fn load_from_bytes_with_format(
+        bytes: &[u8],
+        format: ImageFormat,
+    ) -> Result<Self, IconCaptchaError> {
+        Self::load_from_reader_with_format(Cursor::new(bytes), format)
+    }
 
-            // Create a new image with the area of the icon + 1px
-            let mut new_img: ImageBuffer<Rgba<u8>, Vec<u8>> =
-                ImageBuffer::new(new_width, new_height);
+    /// Load an image from any reader in the given format, without guessing it.
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// use image::ImageFormat;
+    /// let file = std::fs::File::open("captchas/icon5-1.png").unwrap();
+    /// let captcha = IconCaptcha::load_from_reader_with_format(file, ImageFormat::Png).unwrap();
+    /// ```
+    pub fn load_from_reader_with_format<R: Read + Seek>(
+        reader: R,
+        format: ImageFormat,
+    ) -> Result<Self, IconCaptchaError> {
+        let img = ImageReader::with_format(BufReader::new(reader), format).decode()?;
+        Ok(Self::new(img))
+    }
 
-            // Copy pixels not nulls for new image
-            for (x, y, pixel) in img_rgb.enumerate_pixels() {
-                if pixel.0[3] != 0 {
-                    // centralize the icon
-                    let new_x = x - min_x;
-                    let new_y = y - min_y;
-                    new_img.put_pixel(new_x, new_y, *pixel);
-                }
-            }
-            let new_img = DynamicImage::ImageRgba8(new_img);
-            icons.push(new_img);
+    /// Load an image from a URL with a blocking GET request.
+    /// Returns `IconCaptchaError::HttpStatus` if the server doesn't answer with a success status.
+    /// Example:
+    /// ```no_run
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let captcha = IconCaptcha::load_from_url("https://example.com/captcha.png");
+    /// ```
+    #[cfg(feature = "url")]
+    pub fn load_from_url(url: &str) -> Result<Self, IconCaptchaError> {
+        let response = reqwest::blocking::get(url)?;
+        if !response.status().is_success() {
+            return Err(IconCaptchaError::HttpStatus(response.status().as_u16()));
         }
-        icons
+        let bytes = response.bytes()?;
+        Self::load_from_bytes(&bytes)
     }
 
-    fn reflect_image(&self, imgs: Vec<DynamicImage>) -> Vec<DynamicImage> {
-        let mut reflected_image = vec![];
-        for img in imgs {
-            let img = img.to_rgba8();
-            let (width, height) = img.dimensions();
-            let mut new_img = ImageBuffer::new(width, height);
-            for y in 0..height {
-                for x in 0..width {
-                    let pixel = img.get_pixel(x, y);
-                    new_img.put_pixel(width - 1 - x, y, *pixel);
-                }
-            }
+    /// Load an image from a file path without blocking the async runtime.
+    /// The image is decoded on a blocking thread.
+    /// Example:
+    /// ```no_run
+    /// # async fn run() {
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let captcha = IconCaptcha::load_image_async("captchas/icon5-1.png").await;
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub async fn load_image_async(path: &str) -> Result<Self, IconCaptchaError> {
+        let bytes = tokio::fs::read(path).await?;
+        Self::decode_blocking(bytes).await
+    }
 
-            let _ = reflected_image.push(DynamicImage::ImageRgba8(new_img));
+    /// Load an image from a URL without blocking the async runtime.
+    /// The image is decoded on a blocking thread.
+    /// Returns `IconCaptchaError::HttpStatus` if the server doesn't answer with a success status.
+    /// Example:
+    /// ```no_run
+    /// # async fn run() {
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let captcha = IconCaptcha::load_from_url_async("https://example.com/captcha.png").await;
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub async fn load_from_url_async(url: &str) -> Result<Self, IconCaptchaError> {
+        let response = reqwest::get(url).await?;
+        if !response.status().is_success() {
+            return Err(IconCaptchaError::HttpStatus(response.status().as_u16()));
         }
-        reflected_image
+        let bytes = response.bytes().await?;
+        Self::decode_blocking(bytes).await
     }
 
-    fn rotate(&self, image: &DynamicImage) -> Vec<DynamicImage> {
-        let mut img_rotate = vec![
-            image.clone(),
-            image.rotate90(),
-            image.rotate180(),
-            image.rotate270(),
-        ];
-        let img_reflected = self.reflect_image(img_rotate.clone());
-        img_rotate.extend_from_slice(&img_reflected[..]);
-        img_rotate
+    #[cfg(feature = "tokio")]
+    async fn decode_blocking<B>(bytes: B) -> Result<Self, IconCaptchaError>
+    where
+        B: AsRef<[u8]> + Send + 'static,
+    {
+        tokio::task::spawn_blocking(move || Self::load_from_bytes(bytes.as_ref()))
+            .await
+            .map_err(|err| IconCaptchaError::Io(std::io::Error::other(err)))?
     }
 
-    /// Solve the captcha image.
-    /// This method returns the struct Icon with the some informations about the captcha image.
-    ///
+    /// Override the separator colors used to split the captcha into icons.
+    /// Defaults to (64, 64, 64) and (240, 240, 240).
     /// Example:
     /// ```
     /// use iconcaptcha_solver::IconCaptcha;
-    /// let captcha = IconCaptcha::load_from_bytes(vec![0, 1, 2, 3]);
-    /// let icon = captcha.solve();
+    /// let mut captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// captcha.with_delimiter_colors(vec![[64, 64, 64], [250, 250, 250]]);
     /// ```
-    pub fn solve(self) -> Icon {
-        let icons_positions = self.get_positions();
-        let icons_cropped = self.cropped(&icons_positions);
-        let mut icons_repeat: Vec<i32> = vec![0; icons_positions.len()];
-        for (i, img) in icons_cropped.iter().enumerate() {
-            for (j, img2) in icons_cropped.iter().enumerate() {
-                if i == j {
-                    continue;
-                }
-                let imgs_rotate = self.rotate(&img2);
-                let mut diff = 0;
-                'rotation: for ic in imgs_rotate {
-                    for (p1, p2) in img.pixels().zip(ic.pixels()) {
-                        if p1.2[3] != p2.2[3] {
-                            diff += 1;
-                        }
-                    }
-                    if diff == 0 {
-                        icons_repeat[i] = icons_repeat[i] + 1;
-                        break 'rotation;
-                    }
-                    diff = 0;
-                }
-            }
-        }
+    pub fn with_delimiter_colors(&mut self, colors: Vec<[u8; 3]>) -> &mut Self {
+        self.options.delimiter_colors = colors;
+        self
+    }
 
-        let mut index_position_final = 0;
-        let mut index_position = icons_repeat.len() as i32;
-        for (i, n) in icons_repeat.iter().enumerate() {
-            if n < &index_position {
-                index_position = *n;
-                index_position_final = i;
-            }
-        }
-        icons_positions[index_position_final].clone()
+    /// Set how much each RGB channel may differ from a delimiter color and still match it.
+    /// Useful for lossy-encoded captchas; defaults to 0 (exact match).
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let mut captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// captcha.with_color_tolerance(3);
+    /// ```
+    pub fn with_color_tolerance(&mut self, tol: u8) -> &mut Self {
+        self.options.color_tolerance = tol;
+        self
     }
-}
 
-#[cfg(feature = "js")]
-fn solve(mut cx: FunctionContext) -> JsResult<JsObject> {
+    /// Set the row of pixels scanned for delimiters.
+    /// Defaults to row 0, the top edge of the image. Rows past the bottom edge
+    /// scan the last row instead.
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let mut captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// captcha.with_delimiter_row(1);
+    /// ```
+    pub fn with_delimiter_row(&mut self, row: u32) -> &mut Self {
+        self.options.delimiter_row = row;
+        self
+    }
+
+    /// Scan whole columns for delimiters instead of the delimiter row: a column is a
+    /// delimiter when at least `fraction` of its pixels have a delimiter color, so an
+    /// icon touching the scanned row with a delimiter color doesn't split its slot.
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let mut captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// captcha.with_delimiter_coverage(0.9);
+    /// ```
+    pub fn with_delimiter_coverage(&mut self, fraction: f32) -> &mut Self {
+        self.options.delimiter_coverage = Some(fraction);
+        self
+    }
+
+    /// Set the width under which a slot is discarded instead of taken for an icon,
+    /// to ignore the narrow slots left by noise detected as delimiters.
+    /// Defaults to 4 pixels.
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let mut captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// captcha.with_min_slot_width(10);
+    /// ```
+    pub fn with_min_slot_width(&mut self, width: u32) -> &mut Self {
+        self.options.min_slot_width = width;
+        self
+    }
+
+    /// Set the length under which the strip of icons is taken for a truncated image,
+    /// the solve methods failing with `IconCaptchaError::ImageTooSmall` instead of
+    /// answering with the one wide icon detected. The length is the width of the
+    /// region, or its height for vertical captchas. Defaults to 50 pixels, 0 disables it.
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let mut captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// captcha.with_min_length(100);
+    /// ```
+    pub fn with_min_length(&mut self, length: u32) -> &mut Self {
+        self.options.min_length = length;
+        self
+    }
+
+    /// Set the largest number of icons solved, the solve methods failing with
+    /// `IconCaptchaError::TooManyIcons` before comparing them when more are detected,
+    /// as the comparisons grow with the square of the number of icons.
+    /// Guards against broken or crafted images splitting into hundreds of slots.
+    /// Defaults to 20.
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let mut captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// captcha.with_max_icons(8);
+    /// ```
+    pub fn with_max_icons(&mut self, max: usize) -> &mut Self {
+        self.options.max_icons = max;
+        self
+    }
+
+    /// Set whether the empty columns at both ends of the strip, fully transparent
+    /// or of the background color, are left out before looking for the slots,
+    /// so padding around the strip isn't taken for icons. Defaults to false.
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let mut captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// captcha.with_trim_edges(true);
+    /// ```
+    pub fn with_trim_edges(&mut self, trim_edges: bool) -> &mut Self {
+        self.options.trim_edges = trim_edges;
+        self
+    }
+
+    /// Look for the icons only inside the rectangle of `w` by `h` pixels at (`x`, `y`),
+    /// for a strip embedded in a larger image such as a screenshot. The delimiter row
+    /// is then counted from the top of the region, and the returned Icons stay in the
+    /// coordinates of the full image.
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let mut captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// captcha.with_region(0, 0, 320, 50);
+    /// ```
+    pub fn with_region(&mut self, x: u32, y: u32, w: u32, h: u32) -> &mut Self {
+        self.options.region = Some((x, y, w, h));
+        self
+    }
+
+    /// Set whether icons are also compared against their mirrored versions.
+    /// Defaults to true, disable it for captchas that never mirror their icons.
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let mut captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// captcha.with_reflections(false);
+    /// ```
+    pub fn with_reflections(&mut self, reflections: bool) -> &mut Self {
+        self.options.reflections = reflections;
+        self
+    }
+
+    /// Set in how many evenly spaced angles the icons are rotated when comparing them.
+    /// Defaults to 4, the multiples of 90 degrees. Other angles, like the ones of 8 steps
+    /// (45 degrees), are rotated with bilinear interpolation and compared with a small
+    /// per-pixel tolerance to absorb the anti-aliasing.
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let mut captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// captcha.with_rotation_steps(8);
+    /// ```
+    pub fn with_rotation_steps(&mut self, steps: u32) -> &mut Self {
+        self.options.rotation_steps = steps;
+        self
+    }
+
+    /// Set which channels are compared to tell if two icons are the same.
+    /// Defaults to `CompareMode::Alpha`, the silhouette of the icons.
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::{CompareMode, IconCaptcha};
+    /// let mut captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// captcha.with_compare_mode(CompareMode::Rgba { tolerance: 8 });
+    /// ```
+    pub fn with_compare_mode(&mut self, mode: CompareMode) -> &mut Self {
+        self.options.compare_mode = mode;
+        self
+    }
+
+    /// Set the largest ratio of differing pixels for two icons to still be the same icon,
+    /// so a few stray anti-aliased pixels don't break a match.
+    /// Defaults to 0.0, every pixel has to match.
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let mut captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// captcha.with_match_threshold(0.02);
+    /// ```
+    pub fn with_match_threshold(&mut self, threshold: f32) -> &mut Self {
+        self.options.match_threshold = threshold;
+        self
+    }
+
+    /// Resize every cropped icon to fit in `size`x`size` pixels before comparing them,
+    /// so the same icon drawn at slightly different scales still matches. The aspect
+    /// ratio is kept, the icons are centered on a transparent square.
+    /// Icons are compared at their cropped size by default.
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let mut captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// captcha.with_normalized_size(32);
+    /// ```
+    pub fn with_normalized_size(&mut self, size: u32) -> &mut Self {
+        self.options.normalized_size = Some(size);
+        self
+    }
+
+    /// Set how the icons are laid out in the captcha.
+    /// Defaults to `Orientation::Horizontal`, icons from left to right.
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::{IconCaptcha, Orientation};
+    /// let mut captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// captcha.with_orientation(Orientation::Vertical);
+    /// ```
+    pub fn with_orientation(&mut self, orientation: Orientation) -> &mut Self {
+        self.options.orientation = orientation;
+        self
+    }
+
+    /// Downscale the strip of captchas longer than `max_width`, taller for vertical
+    /// ones, keeping the aspect ratio, to speed up the cropping and matching of large
+    /// images. The icons are looked for on the downscaled strip, then cropped from
+    /// the original image around where they were found and downscaled too. The
+    /// delimiters are still detected on the full image, so the returned Icon is
+    /// always in the coordinates of the original image. The downscaled icons are
+    /// blurred, so they are compared with the tolerance of interpolated rotations.
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let mut captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// captcha.with_max_width(160);
+    /// assert_eq!(captcha.solve().unwrap().position, 3);
+    /// ```
+    pub fn with_max_width(&mut self, max_width: u32) -> &mut Self {
+        self.options.max_width = Some(max_width);
+        self
+    }
+
+    /// Also try to match the icons moved by up to `pixels` in each direction, for icons
+    /// off by a pixel after cropping because of their anti-aliasing. Icons of different
+    /// sizes are then compared too, on the canvas holding both. Defaults to 0.
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let mut captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// captcha.with_max_shift(1).with_match_threshold(0.02);
+    /// ```
+    pub fn with_max_shift(&mut self, pixels: u32) -> &mut Self {
+        self.options.max_shift = pixels;
+        self
+    }
+
+    /// Choose how to pick the answer when several icons share the lowest repeat count.
+    /// Defaults to `TieBreak::First`, the first of them in reading order.
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::{IconCaptcha, TieBreak};
+    /// let mut captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// captcha.with_tie_break(TieBreak::MostDistinct);
+    /// ```
+    pub fn with_tie_break(&mut self, tie_break: TieBreak) -> &mut Self {
+        self.options.tie_break = tie_break;
+        self
+    }
+
+    /// Rule out the pairs of icons whose colors differ before comparing their pixels:
+    /// `threshold` is the largest share of pixels, from 0 to 1, that would have to
+    /// change of color for their color histograms to be the same. Speeds up colored
+    /// themes and tells apart icons of the same shape in different colors.
+    /// Disabled by default.
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let mut captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// captcha.with_histogram_threshold(0.2);
+    /// ```
+    pub fn with_histogram_threshold(&mut self, threshold: f32) -> &mut Self {
+        self.options.histogram_threshold = Some(threshold);
+        self
+    }
+
+    /// Treat the pixels with an alpha at or below `threshold` as background, to leave out
+    /// the halo of faint pixels around anti-aliased icons from their bounding box and
+    /// from the comparisons. Defaults to 0, only fully transparent pixels.
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let mut captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// captcha.with_alpha_threshold(32);
+    /// ```
+    pub fn with_alpha_threshold(&mut self, threshold: u8) -> &mut Self {
+        self.options.alpha_threshold = threshold;
+        self
+    }
+
+    /// Tell apart the icons from a solid background color instead of transparency,
+    /// for flattened captchas without an alpha channel such as JPEG ones.
+    /// A pixel is background when each channel differs from `color` by at most `tolerance`.
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let mut captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// captcha.with_background_color([255, 255, 255], 16);
+    /// ```
+    pub fn with_background_color(&mut self, color: [u8; 3], tolerance: u8) -> &mut Self {
+        self.options.background = Some((color, tolerance));
+        self
+    }
+
+    /// Get the decoded captcha image, converted to RGBA8.
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// let (width, height) = (captcha.image().width(), captcha.image().height());
+    /// ```
+    pub fn image(&self) -> &DynamicImage {
+        &self.img
+    }
+
+    /// Take the decoded captcha image out of the solver.
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// let img = captcha.into_image();
+    /// ```
+    pub fn into_image(self) -> DynamicImage {
+        self.img
+    }
+
+    /// Save the captcha image to a file.
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// captcha.save("target/captcha.png").unwrap();
+    /// ```
+    pub fn save(&self, path: &str) -> Result<(), IconCaptchaError> {
+        self.img.save(path).map_err(IconCaptchaError::ImageEncode)
+    }
+
+    /// Save the captcha image to a file in the given format, whatever the extension
+    /// of the path. The formats without transparency, like JPEG, get the color
+    /// of the pixels without their alpha.
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// use image::ImageFormat;
+    /// let captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// captcha.save_as("target/captcha.img", ImageFormat::WebP).unwrap();
+    /// ```
+    pub fn save_as(&self, path: &str, format: ImageFormat) -> Result<(), IconCaptchaError> {
+        Self::save_image(&self.img, Path::new(path), format)
+    }
+
+    fn save_image(
+        img: &DynamicImage,
+        path: &Path,
+        format: ImageFormat,
+    ) -> Result<(), IconCaptchaError> {
+        let img = match format {
+            ImageFormat::Jpeg => Cow::Owned(DynamicImage::ImageRgb8(img.to_rgb8())),
+            _ => Cow::Borrowed(img),
+        };
+        img.save_with_format(path, format)
+            .map_err(IconCaptchaError::ImageEncode)
+    }
+
+    /// Detect the icon slots of the captcha image without solving it.
+    /// Each Icon holds the horizontal bounds of its slot and its center.
+    ///
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// for icon in captcha.positions() {
+    ///     println!("{}", icon);
+    /// }
+    /// ```
+    pub fn positions(&self) -> Vec<Icon> {
+        self.get_positions()
+    }
+
+    /// Columns detected as delimiters (rows for vertical captchas), in order and
+    /// including the edges of the image at 0 and width, or of the region when set.
+    /// A separator several pixels wide gives all of its columns, the slots lie
+    /// between the non adjacent ones.
+    ///
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// let delimiters = captcha.delimiters();
+    /// assert_eq!(delimiters.first(), Some(&0));
+    /// assert_eq!(delimiters.last(), Some(&captcha.image().width()));
+    /// ```
+    pub fn delimiters(&self) -> Vec<u32> {
+        self.get_delimiters()
+            .into_iter()
+            .flat_map(|(first, last)| first..=last)
+            .collect()
+    }
+
+    /// Count the icon slots of the captcha image without comparing the icons,
+    /// a cheap check that the captcha was rendered correctly before solving it.
+    ///
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// assert_eq!(captcha.icon_count(), 5);
+    /// ```
+    pub fn icon_count(&self) -> usize {
+        self.get_positions().len()
+    }
+
+    /// Extract the icons of the captcha image, trimmed to their bounding box.
+    /// The icon at each index corresponds to the Icon at the same index of `positions()`.
+    ///
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// let icons = captcha.icons();
+    /// assert_eq!(icons.len(), captcha.positions().len());
+    /// ```
+    pub fn icons(&self) -> Vec<DynamicImage> {
+        self.segmented().1
+    }
+
+    /// Rotations and reflections of the icon at `index` of `icons()`, the ones the other
+    /// icons are compared with, in order: the rotations starting with the icon itself,
+    /// then their reflections when enabled. Empty when there is no icon at `index`.
+    ///
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// for (i, variant) in captcha.icon_variants(0).iter().enumerate() {
+    ///     variant.save(format!("target/variant_{}.png", i)).unwrap();
+    /// }
+    /// ```
+    pub fn icon_variants(&self, index: usize) -> Vec<DynamicImage> {
+        match self.icons().get(index) {
+            Some(icon) => self.options.rotate(icon),
+            None => vec![],
+        }
+    }
+
+    /// Check if the pixel matches one of the delimiter colors,
+    /// allowing each channel to differ by up to the color tolerance.
+    fn is_delimiter(&self, pixel: &Rgba<u8>) -> bool {
+        let tolerance = self.options.color_tolerance;
+        self.options
+            .delimiter_colors
+            .iter()
+            .any(|color| (0..3).all(|c| pixel[c].abs_diff(color[c]) <= tolerance))
+    }
+
+    /// Check if the pixel is background: with an alpha at or below the alpha threshold,
+    /// or of the background color when set.
+    fn is_background(&self, pixel: &Rgba<u8>) -> bool {
+        match self.options.background {
+            Some((color, tolerance)) => (0..3).all(|c| pixel[c].abs_diff(color[c]) <= tolerance),
+            None => pixel[3] <= self.options.alpha_threshold,
+        }
+    }
+
+    /// Iterate over the detected icons, each paired with its cropped image.
+    ///
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// for (icon, img) in captcha.iter_icons() {
+    ///     println!("{}: {}x{}", icon.position, img.width(), img.height());
+    /// }
+    /// ```
+    pub fn iter_icons(&self) -> impl Iterator<Item = (Icon, DynamicImage)> {
+        let (icons_positions, icons_cropped) = self.segmented();
+        icons_positions.into_iter().zip(icons_cropped)
+    }
+
+    /// Count how many of the other icons each icon matched, in the order of `positions()`.
+    /// The answer is the icon with the lowest count.
+    ///
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// assert_eq!(captcha.match_counts(), vec![1, 1, 0, 1, 1]);
+    /// ```
+    pub fn match_counts(&self) -> Vec<i32> {
+        self.options.repeats(&self.icons())
+    }
+
+    /// Collect what the solver detected and computed, to understand a wrong answer.
+    ///
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// let diagnostics = captcha.diagnostics();
+    /// assert_eq!(diagnostics.icon_count, 5);
+    /// ```
+    pub fn diagnostics(&self) -> Diagnostics {
+        let (icons_positions, icons_cropped) = self.segmented();
+        Diagnostics {
+            delimiters: self.get_delimiters(),
+            icon_count: icons_positions.len(),
+            icon_sizes: icons_cropped.iter().map(|img| img.dimensions()).collect(),
+            repeats: self.options.repeats(&icons_cropped),
+        }
+    }
+
+    /// Save each cropped icon into a directory as `icon_<position>.png`,
+    /// creating the directory if it doesn't exist.
+    ///
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// captcha.save_icons("target/icons").unwrap();
+    /// ```
+    pub fn save_icons(&self, dir: &str) -> Result<(), IconCaptchaError> {
+        self.save_icons_as(dir, ImageFormat::Png)
+    }
+
+    /// Save each cropped icon into a directory in the given format, like `save_icons`,
+    /// as `icon_<position>` with the main extension of the format.
+    ///
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// use image::ImageFormat;
+    /// let captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// captcha.save_icons_as("target/icons_webp", ImageFormat::WebP).unwrap();
+    /// assert!(std::path::Path::new("target/icons_webp/icon_1.webp").exists());
+    /// ```
+    pub fn save_icons_as(&self, dir: &str, format: ImageFormat) -> Result<(), IconCaptchaError> {
+        std::fs::create_dir_all(dir)?;
+        let extension = format.extensions_str().first().unwrap_or(&"img");
+        let (icons_positions, icons_cropped) = self.segmented();
+        for (icon, img) in icons_positions.iter().zip(icons_cropped) {
+            let path = Path::new(dir).join(format!("icon_{}.{}", icon.position, extension));
+            Self::save_image(&img, &path, format)?;
+        }
+        Ok(())
+    }
+
+    /// Detect the delimiters, as runs of consecutive delimiter columns (first, last),
+    /// including the edges of the region, in full image coordinates.
+    fn get_delimiters(&self) -> Vec<(u32, u32)> {
+        let img = &self.img;
+        let (x0, y0, width, height) = self.region();
+        if width == 0 || height == 0 {
+            return vec![];
+        }
+        // length of the strip of icons and of the line scanned across it
+        let (length, across) = match self.options.orientation {
+            Orientation::Horizontal => (width, height),
+            Orientation::Vertical => (height, width),
+        };
+
+        // pixels of the region, from the start of the strip and across it
+        let pixel = |i: u32, j: u32| match self.options.orientation {
+            Orientation::Horizontal => img.get_pixel(x0 + i, y0 + j),
+            Orientation::Vertical => img.get_pixel(x0 + j, y0 + i),
+        };
+        // the delimiters are returned in full image coordinates
+        let origin = match self.options.orientation {
+            Orientation::Horizontal => x0,
+            Orientation::Vertical => y0,
+        };
+
+        // columns of the strip left after trimming the empty ones at its ends
+        let (first, last) = if self.options.trim_edges {
+            let filled = |i: &u32| (0..across).any(|j| !self.is_background(&pixel(*i, j)));
+            match ((0..length).find(filled), (0..length).rfind(filled)) {
+                (Some(first), Some(last)) => (first, last),
+                _ => return vec![(origin, origin + length)],
+            }
+        } else {
+            (0, length - 1)
+        };
+
+        // row scanned for delimiters (column for vertical captchas), kept inside the image
+        let row = self.options.delimiter_row.min(across.saturating_sub(1));
+
+        // every column of the strip, with the end of the strip at `length`:
+        // the edges and the trimmed columns are delimiters too
+        let columns: Vec<bool> = (0..=length)
+            .map(|i| {
+                if i == 0 || i == length || i < first || i > last {
+                    return true;
+                }
+                match self.options.delimiter_coverage {
+                    Some(fraction) => {
+                        let count = (0..across)
+                            .filter(|&j| self.is_delimiter(&pixel(i, j)))
+                            .count();
+                        count as f32 >= fraction * across as f32
+                    }
+                    None => self.is_delimiter(&pixel(i, row)),
+                }
+            })
+            .collect();
+
+        // each delimiter is a run of delimiter columns, as (first, last), so a
+        // separator several pixels wide is a single delimiter, and the runs of
+        // content columns between them are the slots. The background columns
+        // aren't told apart from the content ones: inside a slot they are the
+        // margins of the icon or the gaps between its parts, splitting the slot
+        // there would cut the icon, and the empty ends of the strip are trimmed
+        // with `trim_edges` instead
+        Self::runs(&columns)
+            .into_iter()
+            .filter(|&(delimiter, _, _)| delimiter)
+            .map(|(_, first, last)| (origin + first, origin + last))
+            .collect()
+    }
+
+    /// Region of the image holding the strip, as (x, y, width, height),
+    /// kept inside the image. The whole image when no region was set.
+    fn region(&self) -> (u32, u32, u32, u32) {
+        let (width, height) = self.img.dimensions();
+        match self.options.region {
+            Some((x, y, w, h)) => {
+                let (x, y) = (x.min(width), y.min(height));
+                (x, y, w.min(width - x), h.min(height - y))
+            }
+            None => (0, 0, width, height),
+        }
+    }
+
+    /// Icons to solve, with their positions, failing when the strip is shorter
+    /// than the minimum length or holds more icons than the maximum.
+    fn checked_icons(&self) -> Result<(Vec<Icon>, Vec<DynamicImage>), IconCaptchaError> {
+        let (_, _, width, height) = self.region();
+        let length = match self.options.orientation {
+            Orientation::Horizontal => width,
+            Orientation::Vertical => height,
+        };
+        if length < self.options.min_length {
+            return Err(IconCaptchaError::ImageTooSmall {
+                length,
+                min_length: self.options.min_length,
+            });
+        }
+        let (icons_positions, icons_cropped) = self.segmented();
+        if icons_positions.len() > self.options.max_icons {
+            return Err(IconCaptchaError::TooManyIcons {
+                found: icons_positions.len(),
+                max: self.options.max_icons,
+            });
+        }
+        Ok((icons_positions, icons_cropped))
+    }
+
+    fn get_positions(&self) -> Vec<Icon> {
+        self.slots().into_iter().map(|(icon, ..)| icon).collect()
+    }
+
+    /// Slots of the icons as cropped by `slot`, across the rows of `slot_extent`
+    /// computed once for all of them.
+    fn slots(&self) -> Vec<Slot> {
+        let (x0, y0, width, height) = self.region();
+        let delimiter = self.get_delimiters();
+        if delimiter.is_empty() {
+            return vec![];
+        }
+
+        let mut imgs_positions = vec![];
+
+        for i in 0..delimiter.len() - 1 {
+            // reverse to avoid negative result
+            // start == initial position or color gray
+            // end == end position or color gray
+            // delimiter[end], delimiter[start]
+            let (p_end, p_start) = (delimiter[i + 1].0, delimiter[i].1);
+
+            // skip slots too narrow to hold an icon, like the ones between
+            // delimiters a column or two apart, their width would underflow below
+            if p_end < p_start + 3 {
+                trace!("skipped the slot {}..{}, too narrow", p_start, p_end);
+                continue;
+            }
+            // and the ones left by noise taken for delimiters, the slot holds
+            // the columns from p_start + 1 to p_end - 1 included
+            if (p_end - 1) - (p_start + 1) + 1 < self.options.min_slot_width {
+                trace!(
+                    "skipped the slot {}..{}, below the min width",
+                    p_start,
+                    p_end
+                );
+                continue;
+            }
+
+            //calculate center
+            // (p_end - 1) - (p_start + 1) == icon width
+            // (p_end - 1) - (p_start + 1)) / 2) == icon center
+            // (((p_end - 1) - (p_start + 1)) / 2) + p_start + 1 == icon center position
+            let center = (((p_end - 1) - (p_start + 1)) / 2) + p_start + 1;
+            //end - start - center
+            imgs_positions.push(vec![p_end - 1, p_start + 1, center]);
+        }
+
+        // rows of the slots, columns for vertical captchas
+        let extent = self.slot_extent();
+        let first = extent.0;
+        let strip = self.scaled_strip(extent);
+        let mut slots = Vec::new();
+        for (index, icon) in imgs_positions.iter().enumerate() {
+            let (center_x, center_y) = match self.options.orientation {
+                Orientation::Horizontal => (icon[2], y0 + height / 2),
+                Orientation::Vertical => (x0 + width / 2, icon[2]),
+            };
+            // top left corner of the slot cropped by `slot`
+            let (slot_x, slot_y) = match self.options.orientation {
+                Orientation::Horizontal => (icon[1], first),
+                Orientation::Vertical => (first, icon[1]),
+            };
+            let mut icon = Icon {
+                position: index as u32 + 1,
+                start: icon[1],
+                end: icon[0],
+                center_x,
+                center_y,
+                slot_x,
+                slot_y,
+            };
+            // center of the icon drawn inside the slot, across the strip
+            let area = self.icon_area(&icon, extent, strip.as_ref());
+            let slot = self.slot(area);
+            let bounds = Self::bounding_box(&slot);
+            let (min_x, min_y, max_x, max_y) = bounds;
+            if min_x <= max_x {
+                match self.options.orientation {
+                    Orientation::Horizontal => icon.center_y = area.1 + (min_y + max_y) / 2,
+                    Orientation::Vertical => icon.center_x = area.0 + (min_x + max_x) / 2,
+                }
+            }
+            trace!("slot {}: {}", icon.position, icon);
+            slots.push((icon, slot, bounds));
+        }
+        debug!("{} delimiters, {} slots", delimiter.len(), slots.len());
+        slots
+    }
+
+    /// Runs of consecutive equal values, as (value, first index, last index).
+    fn runs(values: &[bool]) -> Vec<(bool, u32, u32)> {
+        let mut runs: Vec<(bool, u32, u32)> = vec![];
+        for (i, &value) in values.iter().enumerate() {
+            match runs.last_mut() {
+                Some(run) if run.0 == value => run.2 = i as u32,
+                _ => runs.push((value, i as u32, i as u32)),
+            }
+        }
+        runs
+    }
+
+    /// Rows of the strip holding the icons, as (first, last) in full image coordinates:
+    /// the rows with pixels that are neither background nor delimiters, columns for
+    /// vertical captchas. The slots are cropped to them, whatever the size of the
+    /// captcha. `None` when there are no such pixels.
+    ///
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// let (first, last) = captcha.content_extent().unwrap();
+    /// assert!(first <= last && last < captcha.image().height());
+    /// ```
+    pub fn content_extent(&self) -> Option<(u32, u32)> {
+        let (x0, y0, width, height) = self.region();
+        let is_content = |x: u32, y: u32| {
+            let pixel = self.img.get_pixel(x, y);
+            !self.is_background(&pixel) && !self.is_delimiter(&pixel)
+        };
+        let (mut across, along) = match self.options.orientation {
+            Orientation::Horizontal => (y0..y0 + height, x0..x0 + width),
+            Orientation::Vertical => (x0..x0 + width, y0..y0 + height),
+        };
+        let filled = |j: &u32| {
+            along.clone().any(|i| match self.options.orientation {
+                Orientation::Horizontal => is_content(i, *j),
+                Orientation::Vertical => is_content(*j, i),
+            })
+        };
+        let first = across.clone().find(filled)?;
+        let last = across.rfind(filled)?;
+        Some((first, last))
+    }
+
+    /// Rows the slots are cropped to, the whole strip when there is no content.
+    fn slot_extent(&self) -> (u32, u32) {
+        let (x0, y0, width, height) = self.region();
+        let (start, across) = match self.options.orientation {
+            Orientation::Horizontal => (y0, height),
+            Orientation::Vertical => (x0, width),
+        };
+        self.content_extent()
+            .unwrap_or((start, (start + across).saturating_sub(1).max(start)))
+    }
+
+    /// Downscale factor of the strip when it is longer than the max width,
+    /// its height for vertical captchas.
+    fn downscale(&self) -> Option<f32> {
+        let (_, _, width, height) = self.region();
+        let length = match self.options.orientation {
+            Orientation::Horizontal => width,
+            Orientation::Vertical => height,
+        };
+        match self.options.max_width {
+            Some(max_width) if length > max_width => Some(max_width.max(1) as f32 / length as f32),
+            _ => None,
+        }
+    }
+
+    /// The strip across the rows of `slot_extent`, downscaled once when it is longer
+    /// than the max width, so the icons are looked for on the smaller image.
+    fn scaled_strip(&self, (first, last): (u32, u32)) -> Option<ScaledStrip> {
+        let scale = self.downscale()?;
+        let (x0, y0, width, height) = self.region();
+        let (width, height, x, y) = match self.options.orientation {
+            Orientation::Horizontal => (width, last - first + 1, x0, first),
+            Orientation::Vertical => (last - first + 1, height, first, y0),
+        };
+        let scaled = |size: u32| ((size as f32 * scale).round() as u32).max(1);
+        let strip = self.img.crop_imm(x, y, width, height).resize_exact(
+            scaled(width),
+            scaled(height),
+            FilterType::Triangle,
+        );
+        let scales = (
+            strip.width() as f32 / width as f32,
+            strip.height() as f32 / height as f32,
+        );
+        let scales = match self.options.orientation {
+            Orientation::Horizontal => scales,
+            Orientation::Vertical => (scales.1, scales.0),
+        };
+        Some((strip, scales))
+    }
+
+    /// Area of the image holding the icon, as (x, y, width, height): its slot, from
+    /// its start to its end column included, across the rows of `slot_extent`,
+    /// computed once by the caller for all the slots. With the downscaled strip, the
+    /// icon is looked for in the slot on it, and the area is its bounding box scaled
+    /// back to the image, so only the pixels around the icon are processed at full size.
+    fn icon_area(
+        &self,
+        icon: &Icon,
+        (first, last): (u32, u32),
+        strip: Option<&ScaledStrip>,
+    ) -> (u32, u32, u32, u32) {
+        let slot = match self.options.orientation {
+            Orientation::Horizontal => (
+                icon.start,
+                first,
+                icon.end - icon.start + 1,
+                last - first + 1,
+            ),
+            Orientation::Vertical => (
+                first,
+                icon.start,
+                last - first + 1,
+                icon.end - icon.start + 1,
+            ),
+        };
+        let Some(&(ref strip, (along, across))) = strip else {
+            return slot;
+        };
+        let vertical = self.options.orientation == Orientation::Vertical;
+        let (x0, y0, _, _) = self.region();
+        let (offset, length) = if vertical {
+            (y0, strip.height())
+        } else {
+            (x0, strip.width())
+        };
+        // a scaled pixel blends the pixels up to a scaled pixel away from its center,
+        // only the ones computed from the slot alone are kept, the delimiters being
+        // blurred into the others
+        let start = ((((icon.start - offset) as f32) * along + 0.5).ceil() as u32).min(length - 1);
+        let end = ((((icon.end + 1 - offset) as f32) * along - 0.5).floor() as u32)
+            .clamp(start + 1, length);
+        let mut scaled = if vertical {
+            strip.crop_imm(0, start, strip.width(), end - start)
+        } else {
+            strip.crop_imm(start, 0, end - start, strip.height())
+        }
+        .to_rgba8();
+        self.mask(&mut scaled);
+        let (min_x, min_y, max_x, max_y) = Self::bounding_box(&scaled);
+        if min_x > max_x {
+            return slot;
+        }
+        let (min_along, max_along, min_across, max_across) = if vertical {
+            (min_y + start, max_y + start, min_x, max_x)
+        } else {
+            (min_x + start, max_x + start, min_y, max_y)
+        };
+        // back to the image, with the pixels blended into the bounding box and a scaled
+        // pixel more, for the faint ones
+        let lower = |k: u32, scale: f32| ((k as f32 - 1.5) / scale).max(0.0) as u32;
+        let upper = |k: u32, scale: f32| ((k as f32 + 2.5) / scale).ceil() as u32;
+        let (start, end) = (
+            (offset + lower(min_along, along)).clamp(icon.start, icon.end),
+            (offset + upper(max_along, along)).clamp(icon.start, icon.end),
+        );
+        let (top, bottom) = (
+            (first + lower(min_across, across)).min(last),
+            (first + upper(max_across, across)).min(last),
+        );
+        trace!(
+            "icon {} looked for in {}..{} across {}..{}",
+            icon.position,
+            start,
+            end,
+            top,
+            bottom
+        );
+        if vertical {
+            (top, start, bottom - top + 1, end - start + 1)
+        } else {
+            (start, top, end - start + 1, bottom - top + 1)
+        }
+    }
+
+    /// Area of the captcha image holding an icon, given by `icon_area`.
+    fn slot(&self, (x, y, width, height): (u32, u32, u32, u32)) -> ImageBuffer<Rgba<u8>, Vec<u8>> {
+        let mut slot = self.img.crop_imm(x, y, width, height).to_rgba8();
+        self.mask(&mut slot);
+        slot
+    }
+
+    /// Tell the icons apart by their alpha like on transparent captchas. With a
+    /// background color, its pixels are made transparent and the others opaque.
+    /// With an alpha threshold, the pixels at or below it are made transparent.
+    fn mask(&self, slot: &mut ImageBuffer<Rgba<u8>, Vec<u8>>) {
+        if self.options.background.is_some() {
+            for pixel in slot.pixels_mut() {
+                pixel[3] = if self.is_background(pixel) { 0 } else { 255 };
+            }
+        } else if self.options.alpha_threshold > 0 {
+            for pixel in slot.pixels_mut() {
+                if self.is_background(pixel) {
+                    pixel[3] = 0;
+                }
+            }
+        }
+    }
+
+    /// Corners of the bounding box of the non transparent pixels,
+    /// as (min_x, min_y, max_x, max_y).
+    fn bounding_box(img_rgb: &ImageBuffer<Rgba<u8>, Vec<u8>>) -> (u32, u32, u32, u32) {
+        let (width, height) = img_rgb.dimensions();
+        let mut min_x = width;
+        let mut min_y = height;
+        let mut max_x = 0;
+        let mut max_y = 0;
+
+        // It goes through all the pixels and identifies the corners
+        // of the icon's bounding box.
+        //  (min_x, min_y)
+        //   \
+        //    \
+        //     \
+        //      \
+        //       (max_x, max_y)
+
+        for (x, y, pixel) in img_rgb.enumerate_pixels() {
+            if pixel.0[3] != 0 {
+                if x < min_x {
+                    min_x = x;
+                }
+                if y < min_y {
+                    min_y = y;
+                }
+                if x > max_x {
+                    max_x = x;
+                }
+                if y > max_y {
+                    max_y = y;
+                }
+            }
+        }
+        (min_x, min_y, max_x, max_y)
+    }
+
+    /// Positions of the icons, with the icons trimmed from their slot.
+    fn segmented(&self) -> (Vec<Icon>, Vec<DynamicImage>) {
+        let scale = self.downscale();
+        self.slots()
+            .into_iter()
+            .map(|(positions, slot, bounds)| {
+                let icon = Self::trim_to(&slot, bounds);
+                // scaling the trimmed icon gives the same result for identical icons
+                // wherever they sit in their slot
+                let icon = match scale {
+                    Some(scale) => icon.resize_exact(
+                        ((icon.width() as f32 * scale).round() as u32).max(1),
+                        ((icon.height() as f32 * scale).round() as u32).max(1),
+                        FilterType::Triangle,
+                    ),
+                    None => icon,
+                };
+                trace!(
+                    "icon {} cropped to {}x{}",
+                    positions.position,
+                    icon.width(),
+                    icon.height()
+                );
+                (positions, icon)
+            })
+            .unzip()
+    }
+
+    /// Copy the non transparent pixels into an image the size of their bounding box.
+    fn trim(img_rgb: &ImageBuffer<Rgba<u8>, Vec<u8>>) -> DynamicImage {
+        Self::trim_to(img_rgb, Self::bounding_box(img_rgb))
+    }
+
+    /// Copy the non transparent pixels into an image the size of their bounding box,
+    /// given by the caller. A slot without any of them, like a delimiter region taken
+    /// for an icon, gives an empty image, which only matches the other empty ones.
+    fn trim_to(
+        img_rgb: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+        (min_x, min_y, max_x, max_y): (u32, u32, u32, u32),
+    ) -> DynamicImage {
+        if min_x > max_x {
+            return DynamicImage::ImageRgba8(ImageBuffer::new(0, 0));
+        }
+
+        // Calculate the dimensions of the new image
+        // resulting in the area + 1 pixel to fit
+        // completely the icon
+        let new_width = max_x - min_x + 1;
+        let new_height = max_y - min_y + 1;
+
+        // Create a new image with the area of the icon + 1px
+        let mut new_img: ImageBuffer<Rgba<u8>, Vec<u8>> = ImageBuffer::new(new_width, new_height);
+
+        // Copy pixels not nulls for new image
+        for (x, y, pixel) in img_rgb.enumerate_pixels() {
+            if pixel.0[3] != 0 {
+                // centralize the icon
+                let new_x = x - min_x;
+                let new_y = y - min_y;
+                new_img.put_pixel(new_x, new_y, *pixel);
+            }
+        }
+        DynamicImage::ImageRgba8(new_img)
+    }
+}
+
+/// Comparison of the icons, which only depends on the settings.
+impl Options {
+    /// Scale the icon so its longer side is `size` pixels, keeping its aspect ratio,
+    /// and center it on a transparent `size`x`size` image.
+    fn normalized(img: &DynamicImage, size: u32) -> DynamicImage {
+        let mut canvas: ImageBuffer<Rgba<u8>, Vec<u8>> = ImageBuffer::new(size, size);
+        let (width, height) = img.dimensions();
+        if width == 0 || height == 0 {
+            return DynamicImage::ImageRgba8(canvas);
+        }
+        let scaled = |side: u32| ((side * size) as f32 / width.max(height) as f32).round() as u32;
+        let icon = img.resize_exact(
+            scaled(width).max(1),
+            scaled(height).max(1),
+            FilterType::Nearest,
+        );
+        let x = (size - icon.width()) / 2;
+        let y = (size - icon.height()) / 2;
+        image::imageops::replace(&mut canvas, &icon.to_rgba8(), x as i64, y as i64);
+        DynamicImage::ImageRgba8(canvas)
+    }
+
+    /// Rotate the image by any angle around its center, with bilinear interpolation,
+    /// and trim the result to the rotated icon.
+    fn rotate_about_center(image: &DynamicImage, angle: f32) -> DynamicImage {
+        let src = image.to_rgba8();
+        let (width, height) = src.dimensions();
+        let (w, h) = (width as f32, height as f32);
+        let (sin, cos) = angle.sin_cos();
+
+        // canvas big enough to hold the whole rotated image
+        let new_width = (w * cos.abs() + h * sin.abs()).ceil() as u32;
+        let new_height = (w * sin.abs() + h * cos.abs()).ceil() as u32;
+        let (new_w, new_h) = (new_width as f32, new_height as f32);
+
+        let sample = |x: i64, y: i64| -> [f32; 4] {
+            if x < 0 || y < 0 || x >= width as i64 || y >= height as i64 {
+                return [0.0; 4];
+            }
+            src.get_pixel(x as u32, y as u32).0.map(f32::from)
+        };
+
+        let mut new_img: ImageBuffer<Rgba<u8>, Vec<u8>> = ImageBuffer::new(new_width, new_height);
+        for (x, y, pixel) in new_img.enumerate_pixels_mut() {
+            // rotate the center of the new pixel back into the source image
+            let dx = x as f32 + 0.5 - new_w / 2.0;
+            let dy = y as f32 + 0.5 - new_h / 2.0;
+            let sx = dx * cos + dy * sin + w / 2.0 - 0.5;
+            let sy = -dx * sin + dy * cos + h / 2.0 - 0.5;
+
+            let (x0, y0) = (sx.floor(), sy.floor());
+            let (fx, fy) = (sx - x0, sy - y0);
+            let (x0, y0) = (x0 as i64, y0 as i64);
+            let corners = [
+                (sample(x0, y0), (1.0 - fx) * (1.0 - fy)),
+                (sample(x0 + 1, y0), fx * (1.0 - fy)),
+                (sample(x0, y0 + 1), (1.0 - fx) * fy),
+                (sample(x0 + 1, y0 + 1), fx * fy),
+            ];
+            let mut value = [0.0; 4];
+            for (color, weight) in corners {
+                for c in 0..4 {
+                    value[c] += color[c] * weight;
+                }
+            }
+            *pixel = Rgba(value.map(|v| v.round().clamp(0.0, 255.0) as u8));
+        }
+        IconCaptcha::trim(&new_img)
+    }
+
+    fn reflect_image(&self, imgs: Vec<DynamicImage>) -> Vec<DynamicImage> {
+        let mut reflected_image = vec![];
+        for img in imgs {
+            let img = img.to_rgba8();
+            let (width, height) = img.dimensions();
+            let mut new_img = ImageBuffer::new(width, height);
+            for y in 0..height {
+                for x in 0..width {
+                    let pixel = img.get_pixel(x, y);
+                    new_img.put_pixel(width - 1 - x, y, *pixel);
+                }
+            }
+
+            reflected_image.push(DynamicImage::ImageRgba8(new_img));
+        }
+        reflected_image
+    }
+
+    fn rotate(&self, image: &DynamicImage) -> Vec<DynamicImage> {
+        let steps = self.rotation_steps.max(1);
+        let mut img_rotate = vec![];
+        for step in 0..steps {
+            // multiples of 90 degrees are rotated exactly
+            let img = match (step * 4 % steps == 0, step * 4 / steps) {
+                (true, 0) => image.clone(),
+                (true, 1) => image.rotate90(),
+                (true, 2) => image.rotate180(),
+                (true, 3) => image.rotate270(),
+                _ => {
+                    let angle = std::f32::consts::TAU * step as f32 / steps as f32;
+                    Self::rotate_about_center(image, angle)
+                }
+            };
+            img_rotate.push(img);
+        }
+        if !self.reflections {
+            return img_rotate;
+        }
+        let img_reflected = self.reflect_image(img_rotate.clone());
+        img_rotate.extend_from_slice(&img_reflected[..]);
+        img_rotate
+    }
+
+    /// Count, for each icon, how many of the other icons are the same icon
+    /// in any rotation or reflection (when enabled).
+    fn repeats(&self, icons_cropped: &[DynamicImage]) -> Vec<i32> {
+        let (icons_cropped, icons_rotated) = self.prepared(icons_cropped);
+        self.repeats_counted(&icons_cropped, &icons_rotated).0
+    }
+
+    /// The icons as compared, normalized when a size is set, with their rotations
+    /// and reflections, computed once for counting the repeats and ranking.
+    fn prepared<'a>(
+        &self,
+        icons_cropped: &'a [DynamicImage],
+    ) -> (Cow<'a, [DynamicImage]>, Vec<Vec<DynamicImage>>) {
+        let icons_cropped: Cow<[DynamicImage]> = match self.normalized_size {
+            Some(size) => icons_cropped
+                .iter()
+                .map(|img| Self::normalized(img, size))
+                .collect(),
+            None => Cow::Borrowed(icons_cropped),
+        };
+        let icons_rotated = icons_cropped.iter().map(|img| self.rotate(img)).collect();
+        (icons_cropped, icons_rotated)
+    }
+
+    /// Repeat count of every prepared icon, and the number of rotations compared
+    /// to get them.
+    fn repeats_counted(
+        &self,
+        icons_cropped: &[DynamicImage],
+        icons_rotated: &[Vec<DynamicImage>],
+    ) -> (Vec<i32>, u64) {
+        #[cfg(feature = "rayon")]
+        let indexes = (0..icons_cropped.len()).into_par_iter();
+        #[cfg(not(feature = "rayon"))]
+        let indexes = 0..icons_cropped.len();
+
+        let icons_moments: Vec<Moments> = icons_cropped.iter().map(Self::moments).collect();
+        let icons_histograms: Vec<Histogram> = icons_cropped.iter().map(Self::histogram).collect();
+
+        let counts: Vec<(i32, u64)> = indexes
+            .map(|i| {
+                let summaries = (&icons_moments[..], &icons_histograms[..]);
+                self.repeat_count(i, icons_cropped, icons_rotated, summaries)
+            })
+            .collect();
+        // collected in the order of the icons, whatever the order they were counted in
+        let comparisons = counts.iter().map(|(_, c)| c).sum();
+        (counts.into_iter().map(|(r, _)| r).collect(), comparisons)
+    }
+
+    fn repeat_count(
+        &self,
+        i: usize,
+        icons_cropped: &[DynamicImage],
+        icons_rotated: &[Vec<DynamicImage>],
+        (icons_moments, icons_histograms): (&[Moments], &[Histogram]),
+    ) -> (i32, u64) {
+        let img = &icons_cropped[i];
+        let mut comparisons = 0;
+        // the other icons matching this one in any rotation
+        let repeat = icons_rotated
+            .iter()
+            .enumerate()
+            .filter(|(j, imgs_rotate)| {
+                i != *j
+                    && self.moments_close(img, &icons_moments[i], &icons_moments[*j])
+                    && self.histograms_close(&icons_histograms[i], &icons_histograms[*j])
+                    && self.pair_match(
+                        (img, &icons_rotated[i]),
+                        (&icons_cropped[*j], imgs_rotate),
+                        &mut comparisons,
+                    )
+            })
+            .count() as i32;
+        (repeat, comparisons)
+    }
+
+    /// Check if two icons are the same, given with their rotations and reflections,
+    /// counting the rotations compared. Rotating by a multiple of 90 degrees is exact,
+    /// so either icon can be rotated. An interpolated rotation is blurred, and an icon
+    /// may only match the rotation of a copy of it rotated the other way, so both
+    /// ways are tried and the pair counts for both icons.
+    fn pair_match(
+        &self,
+        (a, a_rotated): (&DynamicImage, &[DynamicImage]),
+        (b, b_rotated): (&DynamicImage, &[DynamicImage]),
+        comparisons: &mut u64,
+    ) -> bool {
+        self.rotations_match(a, b_rotated, comparisons)
+            || (self.interpolated() && self.rotations_match(b, a_rotated, comparisons))
+    }
+
+    /// Check if the icon matches one of the rotations and reflections of another icon,
+    /// counting the rotations compared.
+    fn rotations_match(
+        &self,
+        img: &DynamicImage,
+        imgs_rotate: &[DynamicImage],
+        comparisons: &mut u64,
+    ) -> bool {
+        // angles that aren't multiples of 90 degrees blur the edges of the icons
+        let interpolated = self.interpolated();
+        imgs_rotate.iter().any(|ic| {
+            *comparisons += 1;
+            if interpolated {
+                self.fuzzy_match(img, ic)
+            } else if self.max_shift > 0 {
+                self.shifted_match(img, ic)
+            } else {
+                self.exact_match(img, ic)
+            }
+        })
+    }
+
+    /// Compare two icons pixel by pixel, matching when they differ
+    /// by no more than the match threshold.
+    fn exact_match(&self, img: &DynamicImage, ic: &DynamicImage) -> bool {
+        // icons of different sizes can't be the same icon, and zipping
+        // their pixels would only compare the overlapping part
+        if img.dimensions() != ic.dimensions() {
+            return false;
+        }
+        let allowed = self.allowed_diff(img.width() * img.height());
+        let mut diff = 0;
+        for (p1, p2) in img.pixels().zip(ic.pixels()) {
+            if !self.pixels_match(&p1.2, &p2.2, 0) {
+                diff += 1;
+                // one pixel over the threshold is enough to rule out this rotation
+                if diff > allowed {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Whether the icons compared are interpolated, so they are compared with a
+    /// tolerance: when the rotation angles aren't all multiples of 90 degrees,
+    /// or when the icons may be downscaled to the max width.
+    fn interpolated(&self) -> bool {
+        4 % self.rotation_steps.max(1) != 0 || self.max_width.is_some()
+    }
+
+    /// Moments of the alpha channel of the icon around its center.
+    fn moments(img: &DynamicImage) -> Moments {
+        let (width, height) = img.dimensions();
+        let mut moments = Moments::default();
+        for (x, y, pixel) in img.pixels() {
+            // squared distance to the center, in half pixels to stay exact
+            let dx = (2 * x + 1).abs_diff(width) as u64;
+            let dy = (2 * y + 1).abs_diff(height) as u64;
+            let radius = dx * dx + dy * dy;
+            let alpha = pixel[3] as u64;
+            moments.mass += alpha;
+            moments.inertia += radius * alpha;
+            moments.radii += radius;
+            moments.max_radius = moments.max_radius.max(radius);
+        }
+        moments
+    }
+
+    /// Check if the moments of two icons of the size of `img` are close enough
+    /// for the icons to match in any of their rotations and reflections: each pixel
+    /// may change them by the alpha tolerance, and the differing pixels allowed by
+    /// the match threshold by a full alpha range.
+    fn moments_close(&self, img: &DynamicImage, m1: &Moments, m2: &Moments) -> bool {
+        // the moments are only kept by exact rotations of icons that aren't shifted
+        if self.interpolated() || self.max_shift > 0 {
+            return true;
+        }
+        let tolerance = match self.compare_mode {
+            CompareMode::Alpha => 0,
+            CompareMode::Rgba { tolerance } | CompareMode::Luminance { tolerance } => tolerance,
+        } as u64;
+        let pixels = (img.width() * img.height()) as u64;
+        let allowed = self.allowed_diff(img.width() * img.height()) as u64;
+
+        let mass = tolerance * pixels + 255 * allowed;
+        let inertia = tolerance * m1.radii + 255 * allowed * m1.max_radius;
+        m1.mass.abs_diff(m2.mass) <= mass && m1.inertia.abs_diff(m2.inertia) <= inertia
+    }
+
+    /// Color histogram of the visible pixels of the icon.
+    fn histogram(img: &DynamicImage) -> Histogram {
+        let mut histogram = [0.0; 64];
+        let mut visible = 0;
+        for (_, _, pixel) in img.pixels() {
+            if pixel[3] == 0 {
+                continue;
+            }
+            let bin = (pixel[0] as usize >> 6) << 4
+                | (pixel[1] as usize >> 6) << 2
+                | pixel[2] as usize >> 6;
+            histogram[bin] += 1.0;
+            visible += 1;
+        }
+        for count in histogram.iter_mut() {
+            *count /= visible.max(1) as f32;
+        }
+        histogram
+    }
+
+    /// Check if the color histograms of two icons are within the histogram threshold,
+    /// as the share of pixels that would have to change of color bin, from 0 to 1.
+    fn histograms_close(&self, h1: &Histogram, h2: &Histogram) -> bool {
+        match self.histogram_threshold {
+            Some(threshold) => {
+                let distance: f32 = h1.iter().zip(h2).map(|(a, b)| (a - b).abs()).sum();
+                distance / 2.0 <= threshold
+            }
+            None => true,
+        }
+    }
+
+    /// Compare two icons shifted by up to the max shift in each direction,
+    /// matching when one of the alignments differs by no more than the threshold.
+    fn shifted_match(&self, img: &DynamicImage, ic: &DynamicImage) -> bool {
+        let shift = self.max_shift as i64;
+        let (width, height) = (img.width().max(ic.width()), img.height().max(ic.height()));
+        let allowed = self.allowed_diff(width * height);
+        (-shift..=shift).any(|dy| {
+            (-shift..=shift).any(|dx| self.canvas_diff(img, ic, (dx, dy), allowed) <= allowed)
+        })
+    }
+
+    /// Number of differing pixels between `img` and `ic` moved by `offset`, over the
+    /// canvas holding both, the pixels outside of an icon being transparent.
+    /// Counting stops past `limit`.
+    fn canvas_diff(
+        &self,
+        img: &DynamicImage,
+        ic: &DynamicImage,
+        offset: (i64, i64),
+        limit: u32,
+    ) -> u32 {
+        let (dx, dy) = offset;
+        let transparent = Rgba([0, 0, 0, 0]);
+        let pixel = |img: &DynamicImage, x: i64, y: i64| {
+            if x < 0 || y < 0 || x >= img.width() as i64 || y >= img.height() as i64 {
+                transparent
+            } else {
+                img.get_pixel(x as u32, y as u32)
+            }
+        };
+        let mut diff = 0;
+        for y in dy.min(0)..(img.height() as i64).max(dy + ic.height() as i64) {
+            for x in dx.min(0)..(img.width() as i64).max(dx + ic.width() as i64) {
+                if !self.pixels_match(&pixel(img, x, y), &pixel(ic, x - dx, y - dy), 0) {
+                    diff += 1;
+                    if diff > limit {
+                        return diff;
+                    }
+                }
+            }
+        }
+        diff
+    }
+
+    /// Compare two icons allowing the anti-aliasing of an interpolated rotation:
+    /// sizes may differ by a pixel and values by `ROTATION_ALPHA_TOLERANCE`.
+    fn fuzzy_match(&self, img: &DynamicImage, ic: &DynamicImage) -> bool {
+        let (w1, h1) = img.dimensions();
+        let (w2, h2) = ic.dimensions();
+        if w1.abs_diff(w2) > 1 || h1.abs_diff(h2) > 1 {
+            return false;
+        }
+        let (width, height) = (w1.min(w2), h1.min(h2));
+        let allowed = self.allowed_diff(width * height);
+        let mut diff = 0;
+        for y in 0..height {
+            for x in 0..width {
+                let (p1, p2) = (img.get_pixel(x, y), ic.get_pixel(x, y));
+                if !self.pixels_match(&p1, &p2, ROTATION_ALPHA_TOLERANCE) {
+                    diff += 1;
+                    if diff > allowed {
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    /// Number of differing pixels allowed by the match threshold
+    /// for icons of `pixels` pixels.
+    fn allowed_diff(&self, pixels: u32) -> u32 {
+        (self.match_threshold.max(0.0) * pixels as f32) as u32
+    }
+
+    /// Compare two pixels of the icons according to the compare mode,
+    /// allowing `slack` more difference on top of the mode tolerance.
+    fn pixels_match(&self, p1: &Rgba<u8>, p2: &Rgba<u8>, slack: u8) -> bool {
+        let tolerance = match self.compare_mode {
+            CompareMode::Alpha => 0,
+            CompareMode::Rgba { tolerance } | CompareMode::Luminance { tolerance } => tolerance,
+        }
+        .saturating_add(slack);
+        if p1[3].abs_diff(p2[3]) > tolerance {
+            return false;
+        }
+        // the color of transparent pixels doesn't matter
+        if p1[3] == 0 && p2[3] == 0 {
+            return true;
+        }
+        match self.compare_mode {
+            CompareMode::Alpha => true,
+            CompareMode::Rgba { .. } => (0..3).all(|c| p1[c].abs_diff(p2[c]) <= tolerance),
+            CompareMode::Luminance { .. } => {
+                // ITU-R BT.601 luma
+                let luma = |p: &Rgba<u8>| {
+                    ((299 * p[0] as u32 + 587 * p[1] as u32 + 114 * p[2] as u32) / 1000) as u8
+                };
+                luma(p1).abs_diff(luma(p2)) <= tolerance
+            }
+        }
+    }
+
+    /// Fraction of differing pixels between the icon `i` and the closest rotation of
+    /// the other icons, leaving out the icons it matches, whose difference is within
+    /// the match threshold. Icons of different sizes are compared on their common
+    /// canvas, the missing pixels being transparent.
+    fn distance(
+        &self,
+        i: usize,
+        icons_cropped: &[DynamicImage],
+        icons_rotated: &[Vec<DynamicImage>],
+    ) -> f32 {
+        let img = &icons_cropped[i];
+        let mut closest = 1.0f32;
+        for (j, imgs_rotate) in icons_rotated.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+            let mut nearest = 1.0f32;
+            for ic in imgs_rotate {
+                let width = img.width().max(ic.width());
+                let height = img.height().max(ic.height());
+                let diff = self.canvas_diff(img, ic, (0, 0), u32::MAX);
+                // the icons it is the same as don't tell it apart
+                if diff <= self.allowed_diff(width * height) {
+                    nearest = 1.0;
+                    break;
+                }
+                nearest = nearest.min(diff as f32 / (width * height).max(1) as f32);
+            }
+            closest = closest.min(nearest);
+        }
+        closest
+    }
+}
+
+impl IconCaptcha {
+    /// Check what the captcha image holds before solving it, to request another one
+    /// instead of submitting the answer to a blank or malformed captcha.
+    ///
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::{CaptchaKind, IconCaptcha};
+    /// let captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// assert_eq!(captcha.classify(), CaptchaKind::Normal);
+    /// ```
+    pub fn classify(&self) -> CaptchaKind {
+        if self.img.width() == 0 || self.img.height() == 0 {
+            return CaptchaKind::Empty;
+        }
+        let mut pixels = self.img.pixels().map(|(_, _, pixel)| pixel);
+        let first = pixels.next();
+        if pixels.all(|pixel| Some(pixel) == first) {
+            return CaptchaKind::Empty;
+        }
+        let icons = self.icons();
+        if icons.iter().all(|icon| icon.width() == 0) {
+            return CaptchaKind::Empty;
+        }
+        if icons.len() < 3 {
+            return CaptchaKind::TooFewIcons;
+        }
+        let others = icons.len() as i32 - 1;
+        if self
+            .options
+            .repeats(&icons)
+            .iter()
+            .all(|&repeat| repeat == others)
+        {
+            return CaptchaKind::AllIdentical;
+        }
+        CaptchaKind::Normal
+    }
+
+    /// Solve the captcha image.
+    /// This method returns the struct Icon with the some informations about the captcha image.
+    ///
+    /// Returns `IconCaptchaError::NoIconsFound` when no icon was detected and
+    /// `IconCaptchaError::AmbiguousSolution` when every icon matched the others
+    /// the same number of times. When only some of the icons tie, the winner is
+    /// chosen by the tie-break strategy, see `with_tie_break`.
+    /// Returns `IconCaptchaError::ImageTooSmall` when the image is too narrow
+    /// to hold a strip of icons, see `with_min_length`, and
+    /// `IconCaptchaError::TooManyIcons` when it holds too many, see `with_max_icons`.
+    ///
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// let icon = captcha.solve().unwrap();
+    /// ```
+    #[must_use = "the answer to the captcha is in the returned Icon"]
+    pub fn solve(self) -> Result<Icon, IconCaptchaError> {
+        self.solve_ref()
+    }
+
+    /// Solve the captcha image without consuming it, so it can be solved again
+    /// with other settings or saved afterwards. Fails like `solve`.
+    ///
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// if captcha.solve_ref().is_err() {
+    ///     captcha.save("target/failed.png").unwrap();
+    /// }
+    /// ```
+    #[must_use = "the answer to the captcha is in the returned Icon"]
+    pub fn solve_ref(&self) -> Result<Icon, IconCaptchaError> {
+        self.pick(self.ranking()?)
+    }
+
+    /// Solve the captcha image telling the icons apart with a custom matcher instead
+    /// of comparing their rotations. The icons are segmented and cropped with the
+    /// settings of the captcha, then the one matching the fewest others wins, the
+    /// tie-break strategy applying. Fails like `solve`.
+    ///
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::{IconCaptcha, IconMatcher};
+    /// use image::DynamicImage;
+    ///
+    /// // icons of the same size are taken for the same icon
+    /// struct SizeMatcher;
+    /// impl IconMatcher for SizeMatcher {
+    ///     fn are_same(&self, a: &DynamicImage, b: &DynamicImage) -> bool {
+    ///         a.width() == b.width() && a.height() == b.height()
+    ///     }
+    /// }
+    ///
+    /// let captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// let icon = captcha.solve_with_matcher(&captcha.matcher()).unwrap();
+    /// assert_eq!(icon.position, 3);
+    /// let result = captcha.solve_with_matcher(&SizeMatcher);
+    /// ```
+    pub fn solve_with_matcher<M: IconMatcher + ?Sized>(
+        &self,
+        matcher: &M,
+    ) -> Result<Icon, IconCaptchaError> {
+        let (icons_positions, icons_cropped) = self.checked_icons()?;
+        let icons_repeat = (0..icons_cropped.len())
+            .map(|i| {
+                (0..icons_cropped.len())
+                    .filter(|&j| i != j && matcher.are_same(&icons_cropped[i], &icons_cropped[j]))
+                    .count() as i32
+            })
+            .collect();
+        self.pick(self.rank(icons_positions, (&icons_cropped, None), icons_repeat))
+    }
+
+    /// Solve the captcha image by classifying each icon against a set of reference icons,
+    /// like the silhouettes the site draws its captchas from, rather than only comparing
+    /// the icons between them: the icon whose template the fewest others share wins, the
+    /// icons matching no template being compared between them. The templates are trimmed
+    /// to their bounding box and compared at their size with the settings of the captcha.
+    /// Fails like `solve`.
+    ///
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let templates = IconCaptcha::load_image("captchas/icon5-1.png").unwrap().icons();
+    /// let captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// let icon = captcha.solve_against(&templates).unwrap();
+    /// assert_eq!(icon.position, 3);
+    /// ```
+    pub fn solve_against(&self, templates: &[DynamicImage]) -> Result<Icon, IconCaptchaError> {
+        let (icons_positions, icons_cropped) = self.checked_icons()?;
+        let matcher = self.matcher();
+        let templates: Vec<DynamicImage> = templates
+            .iter()
+            .map(|template| Self::trim(&template.to_rgba8()))
+            .collect();
+
+        // first template matching each icon
+        let classes: Vec<Option<usize>> = icons_cropped
+            .iter()
+            .map(|icon| {
+                templates
+                    .iter()
+                    .position(|template| matcher.are_same(icon, template))
+            })
+            .collect();
+        let same = |i: usize, j: usize| match (classes[i], classes[j]) {
+            (Some(a), Some(b)) => a == b,
+            (None, None) => matcher.are_same(&icons_cropped[i], &icons_cropped[j]),
+            _ => false,
+        };
+        let icons_repeat = (0..icons_cropped.len())
+            .map(|i| {
+                (0..icons_cropped.len())
+                    .filter(|&j| i != j && same(i, j))
+                    .count() as i32
+            })
+            .collect();
+        self.pick(self.rank(icons_positions, (&icons_cropped, None), icons_repeat))
+    }
+
+    /// The matcher used by `solve`, with the compare settings of this captcha.
+    pub fn matcher(&self) -> RotationAlphaMatcher {
+        RotationAlphaMatcher {
+            options: self.options.clone(),
+        }
+    }
+
+    /// Solve the captcha image and also return the picture of the answer,
+    /// trimmed to its bounding box, to display it. Fails like `solve`.
+    ///
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// let (icon, image) = captcha.solve_image().unwrap();
+    /// image.save(format!("target/answer_{}.png", icon.position)).unwrap();
+    /// ```
+    pub fn solve_image(self) -> Result<(Icon, DynamicImage), IconCaptchaError> {
+        let icon = self.solve_ref()?;
+        let image = Self::trim(&self.slot(self.icon_area(&icon, self.slot_extent(), None)));
+        Ok((icon, image))
+    }
+
+    /// Solve the captcha image, failing with `IconCaptchaError::UnexpectedIconCount`
+    /// before matching if the number of icons detected isn't `n`.
+    ///
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// let icon = captcha.solve_expecting(5).unwrap();
+    /// ```
+    pub fn solve_expecting(self, n: usize) -> Result<Icon, IconCaptchaError> {
+        let found = self.icon_count();
+        if found != n {
+            return Err(IconCaptchaError::UnexpectedIconCount { expected: n, found });
+        }
+        self.solve()
+    }
+
+    /// Solve the captcha image and tell how confident the answer is.
+    /// The confidence goes from 0.0 to 1.0 and is the margin between the repeat count
+    /// of the chosen icon and the next lowest one, relative to the number of other icons.
+    /// A tie has a confidence of 0.0. The icon is picked and fails like `solve`,
+    /// the tie-break strategy applying.
+    ///
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// let (icon, confidence) = captcha.solve_with_confidence().unwrap();
+    /// if confidence < 0.2 {
+    ///     // fetch another captcha
+    /// }
+    /// ```
+    pub fn solve_with_confidence(self) -> Result<(Icon, f32), IconCaptchaError> {
+        let ranking = self.ranking()?;
+        let confidence = match ranking.get(1) {
+            Some((_, n)) => (n - ranking[0].1) as f32 / (ranking.len() - 1) as f32,
+            None => 0.0,
+        };
+        Ok((self.pick(ranking)?, confidence))
+    }
+
+    /// Solve the captcha image and return every icon with its repeat count,
+    /// sorted from the most unique icon to the most repeated one.
+    /// Icons with the same repeat count keep their order in the image,
+    /// unless the tie-break strategy is `TieBreak::MostDistinct`.
+    /// Empty when the image would fail to solve before matching, like with too many icons.
+    ///
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// for (icon, repeat) in captcha.solve_all() {
+    ///     println!("{} repeats {} times", icon, repeat);
+    /// }
+    /// ```
+    pub fn solve_all(self) -> Vec<(Icon, i32)> {
+        self.ranking().unwrap_or_default()
+    }
+
+    /// Solve the captcha image like `solve`, and measure the time spent
+    /// segmenting the image and matching the icons.
+    ///
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// let (icon, stats) = captcha.solve_with_stats().unwrap();
+    /// println!("{} in {}ns", icon, stats.segmentation_ns + stats.matching_ns);
+    /// ```
+    pub fn solve_with_stats(self) -> Result<(Icon, SolveStats), IconCaptchaError> {
+        let start = Instant::now();
+        let (icons_positions, icons_cropped) = self.checked_icons()?;
+        let segmented = Instant::now();
+        let (icons_cropped, icons_rotated) = self.options.prepared(&icons_cropped);
+        let (icons_repeat, comparisons) =
+            self.options.repeats_counted(&icons_cropped, &icons_rotated);
+        let matched = Instant::now();
+
+        let stats = SolveStats {
+            segmentation_ns: (segmented - start).as_nanos() as u64,
+            matching_ns: (matched - segmented).as_nanos() as u64,
+            comparisons,
+        };
+        let icon = self.pick(self.rank(
+            icons_positions,
+            (&icons_cropped, Some(&icons_rotated)),
+            icons_repeat,
+        ))?;
+        Ok((icon, stats))
+    }
+
+    /// Render the captcha image with the detected slots and the answer drawn over it:
+    /// red lines at the delimiters, a blue dot at the center of each icon
+    /// and a green box around the chosen icon, if there is one.
+    /// Everything is drawn inside the region when set, the box around the slot
+    /// as cropped.
+    ///
+    /// Example:
+    /// ```
+    /// use iconcaptcha_solver::IconCaptcha;
+    /// let captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+    /// captcha.debug_overlay().save("target/overlay.png").unwrap();
+    /// ```
+    pub fn debug_overlay(&self) -> DynamicImage {
+        let mut img = self.img.to_rgba8();
+        let (x0, y0, width, height) = self.region();
+        if width == 0 || height == 0 {
+            return DynamicImage::ImageRgba8(img);
+        }
+        // last column and row of the region
+        let (x1, y1) = (x0 + width - 1, y0 + height - 1);
+        let red = Rgba([255, 0, 0, 255]);
+        let green = Rgba([0, 255, 0, 255]);
+        let blue = Rgba([0, 0, 255, 255]);
+
+        let vertical = self.options.orientation == Orientation::Vertical;
+        // slot of an icon as cropped, as (min_x, min_y, max_x, max_y)
+        let (first, last) = self.slot_extent();
+        let slot = |icon: &Icon| {
+            if vertical {
+                (first, icon.start, last, icon.end)
+            } else {
+                (icon.start, first, icon.end, last)
+            }
+        };
+
+        let icons_positions = self.get_positions();
+        for icon in &icons_positions {
+            // delimiters sit right before and after each slot
+            let (start, end) = if vertical { (y0, y1) } else { (x0, x1) };
+            for line in [
+                icon.start.saturating_sub(1).max(start),
+                (icon.end + 1).min(end),
+            ] {
+                if vertical {
+                    for x in x0..=x1 {
+                        img.put_pixel(x, line, red);
+                    }
+                } else {
+                    for y in y0..=y1 {
+                        img.put_pixel(line, y, red);
+                    }
+                }
+            }
+            for x in icon.center_x.saturating_sub(1).max(x0)..=(icon.center_x + 1).min(x1) {
+                for y in icon.center_y.saturating_sub(1).max(y0)..=(icon.center_y + 1).min(y1) {
+                    img.put_pixel(x, y, blue);
+                }
+            }
+        }
+
+        if let Ok(icon) = self.ranking().and_then(|ranking| self.pick(ranking)) {
+            let (min_x, min_y, max_x, max_y) = slot(&icon);
+            for x in min_x..=max_x {
+                img.put_pixel(x, min_y, green);
+                img.put_pixel(x, max_y, green);
+            }
+            for y in min_y..=max_y {
+                img.put_pixel(min_x, y, green);
+                img.put_pixel(max_x, y, green);
+            }
+        }
+        DynamicImage::ImageRgba8(img)
+    }
+
+    /// Pick the answer from the ranking, failing when there is none.
+    fn pick(&self, mut ranking: Vec<(Icon, i32)>) -> Result<Icon, IconCaptchaError> {
+        if ranking.is_empty() {
+            debug!("no icons found");
+            return Err(IconCaptchaError::NoIconsFound);
+        }
+        if ranking[0].1 == ranking[ranking.len() - 1].1 {
+            debug!("every icon repeats {} times", ranking[0].1);
+            return Err(IconCaptchaError::AmbiguousSolution);
+        }
+        if self.options.tie_break == TieBreak::Error && ranking[0].1 == ranking[1].1 {
+            debug!(
+                "icons {} and {} tie",
+                ranking[0].0.position, ranking[1].0.position
+            );
+            return Err(IconCaptchaError::AmbiguousSolution);
+        }
+        debug!("answer: {}", ranking[0].0);
+        Ok(ranking.swap_remove(0).0)
+    }
+
+    fn ranking(&self) -> Result<Vec<(Icon, i32)>, IconCaptchaError> {
+        let (icons_positions, icons_cropped) = self.checked_icons()?;
+        let (icons_cropped, icons_rotated) = self.options.prepared(&icons_cropped);
+        let icons_repeat = self
+            .options
+            .repeats_counted(&icons_cropped, &icons_rotated)
+            .0;
+        Ok(self.rank(
+            icons_positions,
+            (&icons_cropped, Some(&icons_rotated)),
+            icons_repeat,
+        ))
+    }
+
+    /// Sort the icons from the most unique to the most repeated,
+    /// applying the tie-break strategy. The distance between the tied icons is
+    /// computed on the rotations given, the ones counting the repeats were
+    /// compared with, built here when the repeats were counted by a matcher.
+    fn rank(
+        &self,
+        icons_positions: Vec<Icon>,
+        (icons_cropped, icons_rotated): (&[DynamicImage], Option<&[Vec<DynamicImage>]>),
+        icons_repeat: Vec<i32>,
+    ) -> Vec<(Icon, i32)> {
+        debug!("repeat counts: {:?}", icons_repeat);
+        let mut ranking: Vec<(Icon, i32)> = icons_positions.into_iter().zip(icons_repeat).collect();
+        // stable sort, the first icon wins a tie
+        ranking.sort_by_key(|(_, repeat)| *repeat);
+
+        if self.options.tie_break == TieBreak::MostDistinct && ranking.len() > 1 {
+            let tied = ranking
+                .iter()
+                .take_while(|(_, r)| *r == ranking[0].1)
+                .count();
+            let icons_rotated: Cow<[Vec<DynamicImage>]> = match icons_rotated {
+                Some(icons_rotated) => Cow::Borrowed(icons_rotated),
+                None => icons_cropped
+                    .iter()
+                    .map(|img| self.options.rotate(img))
+                    .collect(),
+            };
+            // stable too, the first icon wins among the equally distinct ones
+            ranking[..tied].sort_by_cached_key(|(icon, _)| {
+                let distance = self.options.distance(
+                    icon.zero_based_position(),
+                    icons_cropped,
+                    &icons_rotated,
+                );
+                std::cmp::Reverse((distance * 1_000_000.0) as u32)
+            });
+        }
+        ranking
+    }
+}
+
+/// Builder collecting the solver settings, to load an already configured IconCaptcha.
+///
+/// Example:
+/// ```
+/// use iconcaptcha_solver::IconCaptchaBuilder;
+/// let captcha = IconCaptchaBuilder::new()
+///     .color_tolerance(3)
+///     .delimiter_row(1)
+///     .load_image("captchas/icon5-1.png")
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct IconCaptchaBuilder {
+    options: Options,
+}
+
+impl IconCaptchaBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// See `IconCaptcha::with_delimiter_colors`.
+    pub fn delimiter_colors(mut self, colors: Vec<[u8; 3]>) -> Self {
+        self.options.delimiter_colors = colors;
+        self
+    }
+
+    /// See `IconCaptcha::with_color_tolerance`.
+    pub fn color_tolerance(mut self, tol: u8) -> Self {
+        self.options.color_tolerance = tol;
+        self
+    }
+
+    /// See `IconCaptcha::with_delimiter_row`.
+    pub fn delimiter_row(mut self, row: u32) -> Self {
+        self.options.delimiter_row = row;
+        self
+    }
+
+    /// See `IconCaptcha::with_delimiter_coverage`.
+    pub fn delimiter_coverage(mut self, fraction: f32) -> Self {
+        self.options.delimiter_coverage = Some(fraction);
+        self
+    }
+
+    /// See `IconCaptcha::with_min_slot_width`.
+    pub fn min_slot_width(mut self, width: u32) -> Self {
+        self.options.min_slot_width = width;
+        self
+    }
+
+    /// See `IconCaptcha::with_min_length`.
+    pub fn min_length(mut self, length: u32) -> Self {
+        self.options.min_length = length;
+        self
+    }
+
+    /// See `IconCaptcha::with_max_icons`.
+    pub fn max_icons(mut self, max: usize) -> Self {
+        self.options.max_icons = max;
+        self
+    }
+
+    /// See `IconCaptcha::with_trim_edges`.
+    pub fn trim_edges(mut self, trim_edges: bool) -> Self {
+        self.options.trim_edges = trim_edges;
+        self
+    }
+
+    /// See `IconCaptcha::with_region`.
+    pub fn region(mut self, x: u32, y: u32, w: u32, h: u32) -> Self {
+        self.options.region = Some((x, y, w, h));
+        self
+    }
+
+    /// See `IconCaptcha::with_reflections`.
+    pub fn reflections(mut self, reflections: bool) -> Self {
+        self.options.reflections = reflections;
+        self
+    }
+
+    /// See `IconCaptcha::with_rotation_steps`.
+    pub fn rotation_steps(mut self, steps: u32) -> Self {
+        self.options.rotation_steps = steps;
+        self
+    }
+
+    /// See `IconCaptcha::with_compare_mode`.
+    pub fn compare_mode(mut self, mode: CompareMode) -> Self {
+        self.options.compare_mode = mode;
+        self
+    }
+
+    /// See `IconCaptcha::with_match_threshold`.
+    pub fn match_threshold(mut self, threshold: f32) -> Self {
+        self.options.match_threshold = threshold;
+        self
+    }
+
+    /// See `IconCaptcha::with_normalized_size`.
+    pub fn normalized_size(mut self, size: u32) -> Self {
+        self.options.normalized_size = Some(size);
+        self
+    }
+
+    /// See `IconCaptcha::with_orientation`.
+    pub fn orientation(mut self, orientation: Orientation) -> Self {
+        self.options.orientation = orientation;
+        self
+    }
+
+    /// See `IconCaptcha::with_max_width`.
+    pub fn max_width(mut self, max_width: u32) -> Self {
+        self.options.max_width = Some(max_width);
+        self
+    }
+
+    /// See `IconCaptcha::with_max_shift`.
+    pub fn max_shift(mut self, pixels: u32) -> Self {
+        self.options.max_shift = pixels;
+        self
+    }
+
+    /// See `IconCaptcha::with_tie_break`.
+    pub fn tie_break(mut self, tie_break: TieBreak) -> Self {
+        self.options.tie_break = tie_break;
+        self
+    }
+
+    /// See `IconCaptcha::with_histogram_threshold`.
+    pub fn histogram_threshold(mut self, threshold: f32) -> Self {
+        self.options.histogram_threshold = Some(threshold);
+        self
+    }
+
+    /// See `IconCaptcha::with_alpha_threshold`.
+    pub fn alpha_threshold(mut self, threshold: u8) -> Self {
+        self.options.alpha_threshold = threshold;
+        self
+    }
+
+    /// See `IconCaptcha::with_background_color`.
+    pub fn background_color(mut self, color: [u8; 3], tolerance: u8) -> Self {
+        self.options.background = Some((color, tolerance));
+        self
+    }
+
+    /// See `IconCaptcha::matcher`.
+    pub fn matcher(self) -> RotationAlphaMatcher {
+        RotationAlphaMatcher {
+            options: self.options,
+        }
+    }
+
+    /// See `IconCaptcha::from_image`.
+    pub fn from_image(self, img: DynamicImage) -> IconCaptcha {
+        self.build(IconCaptcha::from_image(img))
+    }
+
+    /// See `IconCaptcha::from_rgba`.
+    pub fn from_rgba(self, buf: RgbaImage) -> IconCaptcha {
+        self.build(IconCaptcha::from_rgba(buf))
+    }
+
+    /// See `IconCaptcha::load_image`.
+    pub fn load_image(self, path: &str) -> Result<IconCaptcha, IconCaptchaError> {
+        IconCaptcha::load_image(path).map(|captcha| self.build(captcha))
+    }
+
+    /// See `IconCaptcha::load_from_base64`.
+    pub fn load_from_base64(self, base64: &str) -> Result<IconCaptcha, IconCaptchaError> {
+        IconCaptcha::load_from_base64(base64).map(|captcha| self.build(captcha))
+    }
+
+    /// See `IconCaptcha::load_from_data_uri`.
+    pub fn load_from_data_uri(self, uri: &str) -> Result<IconCaptcha, IconCaptchaError> {
+        IconCaptcha::load_from_data_uri(uri).map(|captcha| self.build(captcha))
+    }
+
+    /// See `IconCaptcha::load_from_bytes`.
+    pub fn load_from_bytes(self, bytes: &[u8]) -> Result<IconCaptcha, IconCaptchaError> {
+        IconCaptcha::load_from_bytes(bytes).map(|captcha| self.build(captcha))
+    }
+
+    /// See `IconCaptcha::load_frames_from_bytes`.
+    pub fn load_frames_from_bytes(
+        self,
+        bytes: &[u8],
+    ) -> Result<Vec<IconCaptcha>, IconCaptchaError> {
+        let frames = IconCaptcha::load_frames_from_bytes(bytes)?;
+        Ok(frames
+            .into_iter()
+            .map(|captcha| self.clone().build(captcha))
+            .collect())
+    }
+
+    /// See `IconCaptcha::load_from_reader`.
+    pub fn load_from_reader<R: Read + Seek>(
+        self,
+        reader: R,
+    ) -> Result<IconCaptcha, IconCaptchaError> {
+        IconCaptcha::load_from_reader(reader).map(|captcha| self.build(captcha))
+    }
+
+    /// See `IconCaptcha::load_from_bytes_with_format`.
+    pub fn load_from_bytes_with_format(
+        self,
+        bytes: &[u8],
+        format: ImageFormat,
+    ) -> Result<IconCaptcha, IconCaptchaError> {
+        IconCaptcha::load_from_bytes_with_format(bytes, format).map(|captcha| self.build(captcha))
+    }
+
+    /// See `IconCaptcha::load_from_reader_with_format`.
+    pub fn load_from_reader_with_format<R: Read + Seek>(
+        self,
+        reader: R,
+        format: ImageFormat,
+    ) -> Result<IconCaptcha, IconCaptchaError> {
+        IconCaptcha::load_from_reader_with_format(reader, format).map(|captcha| self.build(captcha))
+    }
+
+    /// See `IconCaptcha::load_from_url`.
+    #[cfg(feature = "url")]
+    pub fn load_from_url(self, url: &str) -> Result<IconCaptcha, IconCaptchaError> {
+        IconCaptcha::load_from_url(url).map(|captcha| self.build(captcha))
+    }
+
+    /// See `IconCaptcha::load_image_async`.
+    #[cfg(feature = "tokio")]
+    pub async fn load_image_async(self, path: &str) -> Result<IconCaptcha, IconCaptchaError> {
+        IconCaptcha::load_image_async(path)
+            .await
+            .map(|captcha| self.build(captcha))
+    }
+
+    /// See `IconCaptcha::load_from_url_async`.
+    #[cfg(feature = "tokio")]
+    pub async fn load_from_url_async(self, url: &str) -> Result<IconCaptcha, IconCaptchaError> {
+        IconCaptcha::load_from_url_async(url)
+            .await
+            .map(|captcha| self.build(captcha))
+    }
+
+    fn build(self, mut captcha: IconCaptcha) -> IconCaptcha {
+        captcha.options = self.options;
+        captcha
+    }
+}
+
+/// Decode the `%XX` escapes of a data URI payload.
+fn percent_decode(payload: &str) -> Result<Vec<u8>, IconCaptchaError> {
+    let bytes = payload.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = bytes
+                .get(i + 1..i + 3)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or(IconCaptchaError::InvalidDataUri("invalid percent escape"))?;
+            decoded.push(hex);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    Ok(decoded)
+}
+
+#[cfg(feature = "js")]
+fn solve(mut cx: FunctionContext) -> JsResult<JsObject> {
     let bs64_img = cx.argument::<JsString>(0)?.value(&mut cx);
     let cap = IconCaptcha::load_from_base64(&bs64_img);
-    if let Err(_) = cap {
-        let obj = cx.empty_object();
-        let msg = cx.string("invalid image");
-        let status = cx.boolean(false);
-        obj.set(&mut cx, "message", msg)?;
-        obj.set(&mut cx, "success", status)?;
-        return Ok(obj);
-    }
-    let icon = cap.unwrap().solve();
+    solve_loaded(&mut cx, cap)
+}
+
+#[cfg(feature = "js")]
+fn solve_bytes(mut cx: FunctionContext) -> JsResult<JsObject> {
+    // a Buffer is an Uint8Array, both are read without copying
+    let bytes = cx.argument::<JsTypedArray<u8>>(0)?;
+    let cap = IconCaptcha::load_from_bytes(bytes.as_slice(&cx));
+    solve_loaded(&mut cx, cap)
+}
+
+/// Solve the loaded captcha into the object returned to JavaScript: the Icon fields
+/// and `success: true`, or the `error` variant name, its `message` and `success: false`.
+#[cfg(feature = "js")]
+fn solve_loaded<'a>(
+    cx: &mut FunctionContext<'a>,
+    cap: Result<IconCaptcha, IconCaptchaError>,
+) -> JsResult<'a, JsObject> {
+    let icon = match cap.and_then(IconCaptcha::solve) {
+        Ok(icon) => icon,
+        Err(err) => {
+            let obj = cx.empty_object();
+            let kind = cx.string(err.kind());
+            let msg = cx.string(err.to_string());
+            let status = cx.boolean(false);
+            obj.set(cx, "error", kind)?;
+            obj.set(cx, "message", msg)?;
+            obj.set(cx, "success", status)?;
+            return Ok(obj);
+        }
+    };
     let obj = cx.empty_object();
     let position = cx.number(icon.position);
-    obj.set(&mut cx, "position", position)?;
+    obj.set(cx, "position", position)?;
+    let index = cx.number(icon.zero_based_position() as u32);
+    obj.set(cx, "index", index)?;
     let start = cx.number(icon.start);
-    obj.set(&mut cx, "start", start)?;
+    obj.set(cx, "start", start)?;
     let end = cx.number(icon.end);
-    obj.set(&mut cx, "end", end)?;
+    obj.set(cx, "end", end)?;
     let center_x = cx.number(icon.center_x);
-    obj.set(&mut cx, "center_x", center_x)?;
+    obj.set(cx, "center_x", center_x)?;
     let center_y = cx.number(icon.center_y);
-    obj.set(&mut cx, "center_y", center_y)?;
+    obj.set(cx, "center_y", center_y)?;
     let status = cx.boolean(true);
-    obj.set(&mut cx, "success", status)?;
+    obj.set(cx, "success", status)?;
     Ok(obj)
 }
 
@@ -321,17 +3198,54 @@ fn solve(mut cx: FunctionContext) -> JsResult<JsObject> {
 #[neon::main]
 fn main(mut cx: ModuleContext) -> NeonResult<()> {
     cx.export_function("solve", solve)?;
+    cx.export_function("solveBytes", solve_bytes)?;
     Ok(())
 }
 
+/// Solve a base64 captcha image from JavaScript, in the same shape as the neon binding:
+/// the Icon fields and `success: true`, or `error`, `message` and `success: false`.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub fn solve_base64(s: &str) -> wasm_bindgen::JsValue {
+    use wasm_bindgen::JsValue;
+
+    let obj = js_sys::Object::new();
+    let set = |key: &str, value: JsValue| {
+        // setting a property on a plain object can't fail
+        let _ = js_sys::Reflect::set(&obj, &JsValue::from_str(key), &value);
+    };
+    match IconCaptcha::load_from_base64(s).and_then(|captcha| captcha.solve()) {
+        Ok(icon) => {
+            set("position", icon.position.into());
+            set("index", (icon.zero_based_position() as u32).into());
+            set("start", icon.start.into());
+            set("end", icon.end.into());
+            set("center_x", icon.center_x.into());
+            set("center_y", icon.center_y.into());
+            set("success", true.into());
+        }
+        Err(err) => {
+            set("error", err.kind().into());
+            set("message", err.to_string().into());
+            set("success", false.into());
+        }
+    }
+    obj.into()
+}
+
 #[cfg(test)]
 mod test {
     use walkdir::WalkDir;
 
     use super::*;
 
-    #[test]
-    fn solving() {
+    /// Answers of the sample captchas, in the order of their file names.
+    const SAMPLE_ANSWERS: [u32; 23] = [
+        3, 4, 2, 2, 3, 3, 3, 2, 2, 1, 3, 3, 1, 5, 1, 5, 1, 4, 2, 6, 1, 8, 1,
+    ];
+
+    /// Solve every sample captcha with the settings applied by `configure`.
+    fn solve_samples(configure: impl Fn(&mut IconCaptcha)) -> Vec<u32> {
         let paths = WalkDir::new("captchas").sort_by_file_name().into_iter();
         let mut imgs = Vec::new();
         for path in paths {
@@ -340,17 +3254,756 @@ mod test {
                 imgs.push(pat);
             }
         }
-        let result = vec![
-            3, 4, 2, 2, 3, 3, 3, 2, 2, 1, 3, 3, 1, 5, 1, 5, 1, 4, 2, 6, 1, 8, 1,
-        ];
 
         let mut result_cap = vec![];
         for img in imgs {
-            let img = IconCaptcha::load_image(&img);
-            let icon = img.solve();
-            //break;
+            let mut img = IconCaptcha::load_image(&img).unwrap();
+            configure(&mut img);
+            let icon = img.solve().unwrap();
             result_cap.push(icon.position);
         }
-        assert_eq!(result, result_cap);
+        result_cap
+    }
+
+    #[test]
+    fn solving() {
+        assert_eq!(solve_samples(|_| {}), SAMPLE_ANSWERS);
+    }
+
+    #[test]
+    fn solving_max_width() {
+        for max_width in [100, 160, 240] {
+            let answers = solve_samples(|captcha| {
+                captcha.with_max_width(max_width);
+            });
+            assert_eq!(answers, SAMPLE_ANSWERS);
+        }
+
+        // a vertical strip is downscaled when taller than the max width
+        let img = IconCaptcha::load_image("captchas/icon5-1.png")
+            .unwrap()
+            .into_image()
+            .rotate90();
+        let mut captcha = IconCaptcha::from_rgba(img.to_rgba8());
+        captcha.with_orientation(Orientation::Vertical);
+        let (plain, icons) = (captcha.solve_ref().unwrap(), captcha.icons());
+        captcha.with_max_width(160);
+        let icon = captcha.solve_ref().unwrap();
+        assert_eq!(icon.position, plain.position);
+        assert_eq!(icon.center_abs(), plain.center_abs());
+        assert!(captcha.icons()[0].height() < icons[0].height());
+    }
+
+    #[test]
+    fn load_from_base64_errors() {
+        let err = IconCaptcha::load_from_base64("not base64!").err().unwrap();
+        assert!(matches!(err, IconCaptchaError::Base64Decode(_)));
+        let err = IconCaptcha::load_from_base64("AAECAw==").err().unwrap();
+        assert!(matches!(err, IconCaptchaError::ImageDecode(_)));
+    }
+
+    fn encode_png(img: &ImageBuffer<Rgba<u8>, Vec<u8>>) -> Vec<u8> {
+        let mut bytes = Cursor::new(Vec::new());
+        img.write_to(&mut bytes, image::ImageFormat::Png).unwrap();
+        bytes.into_inner()
+    }
+
+    #[test]
+    fn positions_edge_delimiters() {
+        let mut img = ImageBuffer::new(20, 10);
+        for x in [0, 9, 10] {
+            img.put_pixel(x, 0, Rgba([64, 64, 64, 255]));
+        }
+        let captcha = IconCaptcha::load_from_bytes(&encode_png(&img)).unwrap();
+        let positions = captcha.positions();
+        assert_eq!(positions.len(), 2);
+        assert_eq!((positions[0].start, positions[0].end), (1, 8));
+        assert_eq!((positions[1].start, positions[1].end), (11, 19));
+    }
+
+    #[test]
+    fn positions_grayscale() {
+        let mut img = image::GrayImage::new(20, 10);
+        img.put_pixel(9, 0, image::Luma([64]));
+        let mut bytes = Cursor::new(Vec::new());
+        img.write_to(&mut bytes, image::ImageFormat::Png).unwrap();
+        let captcha = IconCaptcha::load_from_bytes(bytes.get_ref()).unwrap();
+        assert!(matches!(captcha.image(), DynamicImage::ImageRgba8(_)));
+        assert_eq!(captcha.positions().len(), 2);
+    }
+
+    #[test]
+    fn solving_vertical() {
+        let img = IconCaptcha::load_image("captchas/icon5-1.png")
+            .unwrap()
+            .into_image()
+            .rotate90();
+        let mut captcha = IconCaptcha::load_from_bytes(&encode_png(&img.to_rgba8())).unwrap();
+        captcha.with_orientation(Orientation::Vertical);
+        let icon = captcha.solve_ref().unwrap();
+        assert_eq!(icon.position, 3);
+        assert_eq!(icon.center_x, 24);
+        // the slot starts at row `start`, across the columns holding the icons
+        let (first, _) = captcha.content_extent().unwrap();
+        assert_eq!((icon.slot_x, icon.slot_y), (first, icon.start));
+        assert_eq!(icon.center_rel_x(), 24 - first);
+        assert_eq!(icon.center_rel_y(), icon.center_y - icon.start);
+    }
+
+    #[test]
+    fn save_as() {
+        let captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+        for (path, format) in [
+            ("target/save_as.webp.bin", ImageFormat::WebP),
+            ("target/save_as.jpeg.bin", ImageFormat::Jpeg),
+        ] {
+            captcha.save_as(path, format).unwrap();
+            let bytes = std::fs::read(path).unwrap();
+            assert_eq!(image::guess_format(&bytes).unwrap(), format);
+        }
+        // lossless, the transparency is kept
+        let bytes = std::fs::read("target/save_as.webp.bin").unwrap();
+        let saved = IconCaptcha::load_from_bytes(&bytes).unwrap();
+        assert_eq!(saved.solve().unwrap().position, 3);
+    }
+
+    #[test]
+    fn load_from_reader_io_error() {
+        struct Failing;
+        impl Read for Failing {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::other("connection reset"))
+            }
+        }
+        impl Seek for Failing {
+            fn seek(&mut self, _: std::io::SeekFrom) -> std::io::Result<u64> {
+                Ok(0)
+            }
+        }
+        let err = IconCaptcha::load_from_reader(Failing).err().unwrap();
+        assert!(matches!(err, IconCaptchaError::Io(_)));
+    }
+
+    #[test]
+    fn load_image_missing_file() {
+        let err = IconCaptcha::load_image("captchas/missing.png")
+            .err()
+            .unwrap();
+        assert!(matches!(err, IconCaptchaError::Io(_)));
+    }
+
+    #[test]
+    fn tie_break_strategies() {
+        // icons 2, 3 and 6 all repeat twice, 6 differs the most from the others
+        let path = "captchas/icon7-4.png";
+        let captcha = IconCaptchaBuilder::new()
+            .tie_break(TieBreak::Error)
+            .load_image(path)
+            .unwrap();
+        let err = captcha.clone().solve().err().unwrap();
+        assert!(matches!(err, IconCaptchaError::AmbiguousSolution));
+        let err = captcha.solve_with_confidence().err().unwrap();
+        assert!(matches!(err, IconCaptchaError::AmbiguousSolution));
+
+        let icon = IconCaptchaBuilder::new()
+            .tie_break(TieBreak::MostDistinct)
+            .load_image(path)
+            .unwrap()
+            .solve()
+            .unwrap();
+        assert_eq!(icon.position, 6);
+        let icon = IconCaptcha::load_image(path).unwrap().solve().unwrap();
+        assert_eq!(icon.position, 2);
+        let (icon, confidence) = IconCaptcha::load_image(path)
+            .unwrap()
+            .solve_with_confidence()
+            .unwrap();
+        assert_eq!((icon.position, confidence), (2, 0.0));
+    }
+
+    #[test]
+    fn deterministic() {
+        let paths: Vec<String> = (1..=7)
+            .map(|i| format!("captchas/icon5-{}.png", i))
+            .collect();
+        let expected: Vec<u32> = paths
+            .iter()
+            .map(|path| {
+                IconCaptcha::load_image(path)
+                    .unwrap()
+                    .solve()
+                    .unwrap()
+                    .position
+            })
+            .collect();
+        for _ in 0..3 {
+            let results: Vec<u32> = IconCaptcha::solve_batch(&paths)
+                .into_iter()
+                .map(|result| result.unwrap().position)
+                .collect();
+            assert_eq!(results, expected);
+        }
+
+        // icons 4 and 5 both repeat once, the lowest position wins
+        let ranking = IconCaptcha::load_image("captchas/icon5-2.png")
+            .unwrap()
+            .solve_all();
+        let positions: Vec<u32> = ranking.iter().map(|(icon, _)| icon.position).collect();
+        assert_eq!(&positions[..2], &[4, 5]);
+    }
+
+    #[test]
+    fn solving_flattened() {
+        // the white icons drawn over a black background, without transparency
+        let mut img = IconCaptcha::load_image("captchas/icon5-1.png")
+            .unwrap()
+            .into_image()
+            .to_rgba8();
+        for pixel in img.pixels_mut() {
+            let alpha = pixel[3] as u32;
+            for c in 0..3 {
+                pixel[c] = (pixel[c] as u32 * alpha / 255) as u8;
+            }
+            pixel[3] = 255;
+        }
+        let mut captcha = IconCaptcha::load_from_bytes(&encode_png(&img)).unwrap();
+        captcha.with_background_color([0, 0, 0], 32);
+        assert_eq!(captcha.solve().unwrap().position, 3);
+    }
+
+    #[test]
+    fn solving_empty_slots() {
+        let mut img = ImageBuffer::new(20, 10);
+        img.put_pixel(9, 0, Rgba([64, 64, 64, 255]));
+        let mut captcha = IconCaptcha::load_from_bytes(&encode_png(&img)).unwrap();
+        captcha.with_min_length(0);
+        let icons = captcha.icons();
+        assert!(icons
+            .iter()
+            .all(|icon| icon.width() == 0 && icon.height() == 0));
+        let err = captcha.solve().err().unwrap();
+        assert!(matches!(err, IconCaptchaError::AmbiguousSolution));
+    }
+
+    #[test]
+    fn solve_ensemble() {
+        let load = |path: &str| IconCaptcha::load_image(path).unwrap();
+        let err = IconCaptcha::solve_ensemble(vec![]).err().unwrap();
+        assert!(matches!(err, IconCaptchaError::NoIconsFound));
+        // answers 3 and 4, a tie
+        let images = vec![load("captchas/icon5-1.png"), load("captchas/icon5-2.png")];
+        let err = IconCaptcha::solve_ensemble(images).err().unwrap();
+        assert!(matches!(err, IconCaptchaError::AmbiguousSolution));
+        // a blank rendering doesn't vote
+        let blank = IconCaptcha::from_rgba(ImageBuffer::new(320, 50));
+        let images = vec![blank.clone(), load("captchas/icon5-2.png")];
+        assert_eq!(IconCaptcha::solve_ensemble(images).unwrap().position, 4);
+        // the error of the first image when none solves
+        let kind = blank.solve_ref().err().unwrap().kind();
+        let err = IconCaptcha::solve_ensemble(vec![blank]).err().unwrap();
+        assert_eq!(err.kind(), kind);
+    }
+
+    #[test]
+    fn max_icons() {
+        // a delimiter every 5 columns makes 40 slots
+        let mut img = ImageBuffer::new(200, 10);
+        for x in (0..200).step_by(5) {
+            for y in 0..10 {
+                img.put_pixel(x, y, Rgba([64, 64, 64, 255]));
+            }
+        }
+        let mut captcha = IconCaptcha::load_from_bytes(&encode_png(&img)).unwrap();
+        captcha.with_min_slot_width(0);
+        let err = captcha.solve_ref().err().unwrap();
+        assert!(matches!(
+            err,
+            IconCaptchaError::TooManyIcons { found: 40, max: 20 }
+        ));
+        assert!(captcha.clone().solve_all().is_empty());
+
+        let mut captcha = IconCaptcha::load_image("captchas/icon8-1.png").unwrap();
+        captcha.with_max_icons(7);
+        let err = captcha.solve_ref().err().unwrap();
+        assert!(matches!(
+            err,
+            IconCaptchaError::TooManyIcons { found: 8, max: 7 }
+        ));
+    }
+
+    #[test]
+    fn solve_against() {
+        let captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+        let icons = captcha.icons();
+        // without the template of the answer, it is the one icon matching none
+        let mut templates = icons.clone();
+        templates.remove(2);
+        assert_eq!(captcha.solve_against(&templates).unwrap().position, 3);
+        // without any template, like the self-comparison
+        assert_eq!(captcha.solve_against(&[]).unwrap().position, 3);
+    }
+
+    #[test]
+    fn min_length() {
+        let captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+        let truncated = IconCaptcha::from_image(captcha.img.crop_imm(0, 0, 40, 50));
+        let err = truncated.solve_ref().err().unwrap();
+        assert!(matches!(
+            err,
+            IconCaptchaError::ImageTooSmall {
+                length: 40,
+                min_length: 50
+            }
+        ));
+        let mut vertical = IconCaptcha::from_image(captcha.img.rotate90());
+        vertical.with_orientation(Orientation::Vertical);
+        assert!(vertical.solve_ref().is_ok());
+    }
+
+    #[test]
+    fn load_gif_frames() {
+        let captcha = IconCaptcha::load_image("captchas/icon5-1.png")
+            .unwrap()
+            .into_image()
+            .to_rgba8();
+        let blank = ImageBuffer::new(captcha.width(), captcha.height());
+        let mut bytes = Vec::new();
+        {
+            let mut encoder = image::codecs::gif::GifEncoder::new(&mut bytes);
+            encoder
+                .encode_frames([image::Frame::new(blank), image::Frame::new(captcha)])
+                .unwrap();
+        }
+        let frames = IconCaptcha::load_frames_from_bytes(&bytes).unwrap();
+        assert_eq!(frames.len(), 2);
+        assert!(frames[0].solve_ref().is_err());
+        assert_eq!(frames[1].icon_count(), 5);
+    }
+
+    #[test]
+    fn delimiter_coverage() {
+        let mut img = ImageBuffer::new(40, 10);
+        for y in 0..10 {
+            img.put_pixel(19, y, Rgba([64, 64, 64, 255]));
+        }
+        // an icon pixel with the delimiter color on the scanned row
+        img.put_pixel(9, 0, Rgba([64, 64, 64, 255]));
+        let mut captcha = IconCaptcha::load_from_bytes(&encode_png(&img)).unwrap();
+        assert_eq!(captcha.positions().len(), 3);
+        captcha.with_delimiter_coverage(0.9);
+        assert_eq!(captcha.positions().len(), 2);
+    }
+
+    #[test]
+    fn trim_edges() {
+        // transparent padding of 10 columns around the strip
+        let strip = IconCaptcha::load_image("captchas/icon5-1.png")
+            .unwrap()
+            .into_image()
+            .to_rgba8();
+        let mut img = ImageBuffer::new(strip.width() + 20, strip.height());
+        image::imageops::replace(&mut img, &strip, 10, 0);
+        // delimiters at both ends of the strip leave empty slots in the padding
+        for y in 0..img.height() {
+            img.put_pixel(9, y, Rgba([64, 64, 64, 255]));
+            img.put_pixel(strip.width() + 10, y, Rgba([64, 64, 64, 255]));
+        }
+        let mut captcha = IconCaptcha::load_from_bytes(&encode_png(&img)).unwrap();
+        assert_eq!(captcha.icon_count(), 7);
+        captcha.with_trim_edges(true);
+        assert_eq!(captcha.icon_count(), 5);
+        assert_eq!(captcha.solve().unwrap().position, 3);
+
+        // without delimiters in the padding, the last icon keeps its last column
+        let mut img = ImageBuffer::new(strip.width() + 20, strip.height());
+        image::imageops::replace(&mut img, &strip, 10, 0);
+        let mut captcha = IconCaptcha::from_rgba(img);
+        captcha.with_trim_edges(true);
+        let plain = IconCaptcha::from_rgba(strip.clone());
+        let sizes = |icons: Vec<DynamicImage>| -> Vec<(u32, u32)> {
+            icons.iter().map(|icon| icon.dimensions()).collect()
+        };
+        assert_eq!(sizes(captcha.icons()), sizes(plain.icons()));
+        // and ends on it, not in the padding
+        let filled = (0..strip.width())
+            .rfind(|&x| (0..strip.height()).any(|y| strip.get_pixel(x, y)[3] != 0))
+            .unwrap();
+        assert_eq!(captcha.positions().pop().unwrap().end, filled + 10);
+
+        // no padding to trim, the answers stay the same
+        assert_eq!(
+            solve_samples(|captcha| {
+                captcha.with_trim_edges(true);
+            }),
+            SAMPLE_ANSWERS
+        );
+    }
+
+    #[test]
+    fn moments_invariant() {
+        let captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+        let icon = captcha.icons().swap_remove(0);
+        let moments = Options::moments(&icon);
+        for other in [icon.rotate90(), icon.rotate270(), icon.fliph()] {
+            let other = Options::moments(&other);
+            assert_eq!((moments.mass, moments.inertia), (other.mass, other.inertia));
+        }
+    }
+
+    #[test]
+    fn shifted_match() {
+        let square = filled(4, 4, [0, 0, 0, 255]);
+        // the same square with a stray anti-aliased pixel widening its bounding box
+        let mut shifted = ImageBuffer::new(5, 4);
+        image::imageops::replace(&mut shifted, &square.to_rgba8(), 1, 0);
+        shifted.put_pixel(0, 0, Rgba([0, 0, 0, 255]));
+        let shifted = DynamicImage::ImageRgba8(shifted);
+
+        let mut options = Options {
+            match_threshold: 0.05,
+            ..Options::default()
+        };
+        assert!(!options.shifted_match(&square, &shifted));
+        options.max_shift = 1;
+        assert!(options.shifted_match(&square, &shifted));
+    }
+
+    #[test]
+    fn delimiter_widths() {
+        // separators 1, 3 and 6 pixels wide between slots of 10 pixels
+        let mut img = ImageBuffer::new(50, 10);
+        for x in [0, 11, 12, 13, 24, 25, 26, 27, 28, 29, 40, 49] {
+            for y in 0..10 {
+                img.put_pixel(x, y, Rgba([64, 64, 64, 255]));
+            }
+        }
+        let captcha = IconCaptcha::load_from_bytes(&encode_png(&img)).unwrap();
+        let slots: Vec<(u32, u32)> = captcha
+            .positions()
+            .iter()
+            .map(|icon| (icon.start, icon.end))
+            .collect();
+        assert_eq!(slots, vec![(1, 10), (14, 23), (30, 39), (41, 48)]);
+    }
+
+    #[test]
+    fn min_slot_width() {
+        let mut img = ImageBuffer::new(30, 10);
+        // noise columns 12 and 15 leave a slot of the 2 columns 13 and 14
+        for x in [0, 12, 15, 29] {
+            img.put_pixel(x, 0, Rgba([64, 64, 64, 255]));
+        }
+        let mut captcha = IconCaptcha::load_from_bytes(&encode_png(&img)).unwrap();
+        assert_eq!(captcha.icon_count(), 2);
+        captcha.with_min_slot_width(2);
+        assert_eq!(captcha.icon_count(), 3);
+        captcha.with_min_slot_width(3);
+        assert_eq!(captcha.icon_count(), 2);
+
+        // columns 12 and 17 leave a slot of the 4 columns 13 to 16, kept by default
+        let mut img = ImageBuffer::new(30, 10);
+        for x in [0, 12, 17, 29] {
+            img.put_pixel(x, 0, Rgba([64, 64, 64, 255]));
+        }
+        let captcha = IconCaptcha::load_from_bytes(&encode_png(&img)).unwrap();
+        assert_eq!(captcha.icon_count(), 3);
+    }
+
+    #[test]
+    fn slot_last_column() {
+        // a slot from column 1 to 9, with an icon drawn up to its last column
+        let mut img = ImageBuffer::new(11, 1);
+        for x in [0, 10] {
+            img.put_pixel(x, 0, Rgba([64, 64, 64, 255]));
+        }
+        for x in [5, 9] {
+            img.put_pixel(x, 0, Rgba([0, 0, 0, 255]));
+        }
+        let captcha = IconCaptcha::from_rgba(img);
+        let icon = &captcha.positions()[0];
+        assert_eq!((icon.start, icon.end), (1, 9));
+        assert_eq!(captcha.icons()[0].dimensions(), (5, 1));
+    }
+
+    #[test]
+    fn classify() {
+        let blank = ImageBuffer::from_pixel(320, 50, Rgba([255, 255, 255, 255]));
+        let captcha = IconCaptcha::load_from_bytes(&encode_png(&blank)).unwrap();
+        assert_eq!(captcha.classify(), CaptchaKind::Empty);
+        for (width, height) in [(0, 12), (12, 0), (0, 0)] {
+            let captcha = IconCaptcha::from_rgba(RgbaImage::new(width, height));
+            assert_eq!(captcha.classify(), CaptchaKind::Empty);
+        }
+
+        // the first icon of a captcha repeated in every slot
+        let strip = IconCaptcha::load_image("captchas/icon5-1.png")
+            .unwrap()
+            .into_image()
+            .to_rgba8();
+        let mut img = strip.clone();
+        for x in (64..320).step_by(64) {
+            image::imageops::replace(&mut img, &*strip.view(1, 0, 63, 50), x + 1, 0);
+        }
+        let captcha = IconCaptcha::load_from_bytes(&encode_png(&img)).unwrap();
+        assert_eq!(captcha.classify(), CaptchaKind::AllIdentical);
+
+        let two = strip.view(0, 0, 128, 50).to_image();
+        let captcha = IconCaptcha::load_from_bytes(&encode_png(&two)).unwrap();
+        assert_eq!(captcha.classify(), CaptchaKind::TooFewIcons);
+    }
+
+    #[test]
+    fn solving_16_bit() {
+        let img = IconCaptcha::load_image("captchas/icon5-1.png")
+            .unwrap()
+            .into_image();
+        let img = DynamicImage::ImageRgba16(img.to_rgba16());
+        let mut bytes = Cursor::new(Vec::new());
+        img.write_to(&mut bytes, image::ImageFormat::Png).unwrap();
+        let captcha = IconCaptcha::load_from_bytes(bytes.get_ref()).unwrap();
+        assert_eq!(captcha.icon_count(), 5);
+        assert_eq!(captcha.solve().unwrap().position, 3);
+    }
+
+    #[test]
+    fn solving_rotated_45() {
+        let mut captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+        captcha.with_rotation_steps(8);
+        // the second icon replaced by the first one rotated 45 degrees
+        let rotated = captcha.icon_variants(0)[1].to_rgba8();
+        let slot = captcha.positions()[1].clone();
+        let mut img = captcha.image().to_rgba8();
+        for x in slot.start..=slot.end {
+            for y in 0..img.height() {
+                img.put_pixel(x, y, Rgba([0, 0, 0, 0]));
+            }
+        }
+        let x = slot.center_x - rotated.width() / 2;
+        let y = slot.center_y - rotated.height() / 2;
+        image::imageops::replace(&mut img, &rotated, x as i64, y as i64);
+
+        let mut captcha = IconCaptcha::from_rgba(img);
+        captcha.with_rotation_steps(8);
+        let counts = captcha.match_counts();
+        assert_eq!(counts, vec![1, 1, 0, 1, 1]);
+        assert_eq!(captcha.solve_ref().unwrap().position, 3);
+        // the pair matches whichever icon is rotated
+        let icons = captcha.icons();
+        let matcher = captcha.matcher();
+        assert!(matcher.are_same(&icons[0], &icons[1]));
+        assert!(matcher.are_same(&icons[1], &icons[0]));
+    }
+
+    #[test]
+    fn rotations_match_order() {
+        let icon = filled(4, 4, [0, 0, 0, 255]);
+        let mut other = icon.to_rgba8();
+        other.put_pixel(0, 0, Rgba([0, 0, 0, 0]));
+        let other = DynamicImage::ImageRgba8(other);
+
+        // a rotation that doesn't match must not change how the next ones compare
+        let options = Options::default();
+        for rotations in [[other.clone(), icon.clone()], [icon.clone(), other.clone()]] {
+            assert!(options.rotations_match(&icon, &rotations, &mut 0));
+        }
+        assert!(!options.rotations_match(&icon, &[other.clone(), other], &mut 0));
+    }
+
+    #[test]
+    fn load_from_data_uri() {
+        let bytes = std::fs::read("captchas/icon5-1.png").unwrap();
+        let base64 = format!("data:image/png;base64,{}", BASE64_STANDARD.encode(&bytes));
+        let captcha = IconCaptcha::load_from_data_uri(&base64).unwrap();
+        assert_eq!(captcha.solve().unwrap().position, 3);
+
+        let percent: String = bytes.iter().map(|b| format!("%{:02X}", b)).collect();
+        let percent = format!("DATA:image/png,{}", percent);
+        assert!(IconCaptcha::load_from_data_uri(&percent).is_ok());
+
+        for uri in [
+            "image/png;base64,AAAA",
+            "data:text/plain;base64,AAAA",
+            "data:image/png",
+        ] {
+            let err = IconCaptcha::load_from_data_uri(uri).err().unwrap();
+            assert!(matches!(err, IconCaptchaError::InvalidDataUri(_)));
+        }
+    }
+
+    #[test]
+    fn solving_region() {
+        // the captcha pasted in a larger screenshot
+        let strip = IconCaptcha::load_image("captchas/icon5-1.png")
+            .unwrap()
+            .into_image()
+            .to_rgba8();
+        let mut img = ImageBuffer::from_pixel(500, 200, Rgba([64, 64, 64, 255]));
+        image::imageops::replace(&mut img, &strip, 100, 80);
+        let mut captcha = IconCaptcha::load_from_bytes(&encode_png(&img)).unwrap();
+        captcha.with_region(100, 80, strip.width(), strip.height());
+        let icon = captcha.solve_ref().unwrap();
+        assert_eq!(icon.position, 3);
+        assert_eq!(icon.click_point(), (260, 104));
+        let plain = IconCaptcha::from_rgba(strip.clone()).solve().unwrap();
+        assert_eq!(icon.center_rel(), plain.center_rel());
+
+        // the overlay is drawn inside the region only
+        let overlay = captcha.debug_overlay().to_rgba8();
+        let inside = |x: u32, y: u32| {
+            (100..100 + strip.width()).contains(&x) && (80..80 + strip.height()).contains(&y)
+        };
+        let mut green = 0;
+        for (x, y, pixel) in overlay.enumerate_pixels() {
+            if !inside(x, y) {
+                assert_eq!(pixel, img.get_pixel(x, y), "drawn at ({}, {})", x, y);
+            } else if *pixel == Rgba([0, 255, 0, 255]) {
+                green += 1;
+            }
+        }
+        assert!(green > 0);
+    }
+
+    #[test]
+    fn solving_tall() {
+        // the captcha drawn 30 pixels down in an image 80 pixels tall
+        let strip = IconCaptcha::load_image("captchas/icon5-1.png")
+            .unwrap()
+            .into_image()
+            .to_rgba8();
+        let mut img = ImageBuffer::new(strip.width(), 80);
+        image::imageops::replace(&mut img, &strip, 0, 30);
+        let mut captcha = IconCaptcha::load_from_bytes(&encode_png(&img)).unwrap();
+        captcha.with_delimiter_row(30);
+        let (first, last) = captcha.content_extent().unwrap();
+        assert!(first >= 30 && first < last);
+        let icon = captcha.solve().unwrap();
+        assert_eq!(icon.position, 3);
+        assert_eq!(icon.click_point(), (160, 54));
+    }
+
+    #[test]
+    fn alpha_threshold() {
+        let mut img = ImageBuffer::new(20, 10);
+        img.put_pixel(10, 0, Rgba([64, 64, 64, 255]));
+        img.put_pixel(3, 3, Rgba([255, 255, 255, 255]));
+        // a faint anti-aliasing pixel away from the icon
+        img.put_pixel(7, 6, Rgba([255, 255, 255, 16]));
+        let mut captcha = IconCaptcha::load_from_bytes(&encode_png(&img)).unwrap();
+        assert_eq!(captcha.icons()[0].dimensions(), (5, 4));
+        captcha.with_alpha_threshold(32);
+        assert_eq!(captcha.icons()[0].dimensions(), (1, 1));
+    }
+
+    #[test]
+    fn icons_match_repeats() {
+        // the pairs of matching icons are the ones solve counts
+        let captcha = IconCaptcha::load_image("captchas/icon5-1.png").unwrap();
+        let icons = captcha.icons();
+        let repeats: Vec<i32> = (0..icons.len())
+            .map(|i| {
+                (0..icons.len())
+                    .filter(|&j| i != j && icons_match(&icons[i], &icons[j]))
+                    .count() as i32
+            })
+            .collect();
+        assert_eq!(repeats, Options::default().repeats(&icons));
+        assert!(icons_match(&icons[1], &icons[1].fliph()));
+    }
+
+    #[test]
+    fn histogram_threshold() {
+        let red = filled(4, 4, [255, 0, 0, 255]);
+        let blue = filled(4, 4, [0, 0, 255, 255]);
+        assert!(IconCaptchaBuilder::new().matcher().are_same(&red, &blue));
+        let matcher = IconCaptchaBuilder::new().histogram_threshold(0.2).matcher();
+        assert!(!matcher.are_same(&red, &blue));
+        assert!(matcher.are_same(&red, &red.rotate90()));
+    }
+
+    /// An icon of `width`x`height` pixels all of the color `rgba`.
+    fn filled(width: u32, height: u32, rgba: [u8; 4]) -> DynamicImage {
+        DynamicImage::ImageRgba8(ImageBuffer::from_pixel(width, height, Rgba(rgba)))
+    }
+
+    #[test]
+    fn different_sizes() {
+        let square = filled(4, 4, [0, 0, 0, 255]);
+        // the same square with an extra transparent row, matching it on their overlap
+        let taller = DynamicImage::ImageRgba8(ImageBuffer::from_fn(4, 5, |_, y| {
+            Rgba([0, 0, 0, if y < 4 { 255 } else { 0 }])
+        }));
+        let options = Options::default();
+        assert_eq!(
+            options.repeats(&[square.clone(), taller, square]),
+            vec![1, 0, 1]
+        );
+    }
+
+    #[test]
+    fn reflections() {
+        // an L shape, its mirror image is none of its rotations
+        let l = DynamicImage::ImageRgba8(ImageBuffer::from_fn(2, 3, |x, y| {
+            Rgba([0, 0, 0, if x == 0 || y == 2 { 255 } else { 0 }])
+        }));
+        let icons = [l.clone(), l.fliph(), l.rotate90()];
+        let mut options = Options::default();
+        assert_eq!(options.repeats(&icons), vec![2, 2, 2]);
+        options.reflections = false;
+        assert_eq!(options.repeats(&icons), vec![1, 0, 1]);
+    }
+
+    #[test]
+    fn compare_mode() {
+        // the same square silhouette in red, almost red and blue
+        let icons = [
+            filled(4, 4, [255, 0, 0, 255]),
+            filled(4, 4, [250, 4, 0, 255]),
+            filled(4, 4, [0, 0, 255, 255]),
+        ];
+        let mut options = Options::default();
+        assert_eq!(options.repeats(&icons), vec![2, 2, 2]);
+        options.compare_mode = CompareMode::Rgba { tolerance: 8 };
+        assert_eq!(options.repeats(&icons), vec![1, 1, 0]);
+        options.compare_mode = CompareMode::Luminance { tolerance: 8 };
+        assert_eq!(options.repeats(&icons), vec![1, 1, 0]);
+    }
+
+    #[test]
+    fn match_threshold() {
+        let square = filled(10, 10, [0, 0, 0, 255]);
+        // the same square with a stray transparent pixel, 1% of the pixels
+        let mut holed = square.to_rgba8();
+        holed.put_pixel(4, 4, Rgba([0, 0, 0, 0]));
+        let icons = [square, DynamicImage::ImageRgba8(holed)];
+
+        let mut options = Options::default();
+        assert_eq!(options.repeats(&icons), vec![0, 0]);
+        options.match_threshold = 0.005;
+        assert_eq!(options.repeats(&icons), vec![0, 0]);
+        options.match_threshold = 0.02;
+        assert_eq!(options.repeats(&icons), vec![1, 1]);
+    }
+
+    #[test]
+    fn normalized_size() {
+        // an L shape and the same shape drawn twice as large
+        let small = DynamicImage::ImageRgba8(ImageBuffer::from_fn(4, 4, |x, y| {
+            Rgba([0, 0, 0, if x == 0 || y == 3 { 255 } else { 0 }])
+        }));
+        let large = small.resize_exact(8, 8, FilterType::Nearest);
+        let icons = [small, large];
+        let mut options = Options::default();
+        assert_eq!(options.repeats(&icons), vec![0, 0]);
+        options.normalized_size = Some(16);
+        assert_eq!(options.repeats(&icons), vec![1, 1]);
+
+        // a bar doesn't become a square once normalized
+        let icons = [
+            filled(2, 10, [0, 0, 0, 255]),
+            filled(10, 10, [0, 0, 0, 255]),
+        ];
+        assert_eq!(options.repeats(&icons), vec![0, 0]);
+        let matcher = IconCaptchaBuilder::new().normalized_size(16).matcher();
+        assert!(!matcher.are_same(&icons[0], &icons[1]));
     }
 }
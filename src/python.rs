@@ -0,0 +1,72 @@
+//! Python binding, built with maturin when the `python` feature is enabled.
+
+use pyo3::exceptions::{PyIOError, PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::{Icon, IconCaptchaError};
+
+impl From<IconCaptchaError> for PyErr {
+    fn from(err: IconCaptchaError) -> Self {
+        match err {
+            IconCaptchaError::Io(_) => PyIOError::new_err(err.to_string()),
+            IconCaptchaError::Base64Decode(_)
+            | IconCaptchaError::ImageDecode(_)
+            | IconCaptchaError::InvalidDataUri(_)
+            | IconCaptchaError::ImageTooSmall { .. } => PyValueError::new_err(err.to_string()),
+            _ => PyRuntimeError::new_err(err.to_string()),
+        }
+    }
+}
+
+fn icon_to_dict<'py>(py: Python<'py>, icon: &Icon) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("position", icon.position)?;
+    dict.set_item("index", icon.zero_based_position())?;
+    dict.set_item("start", icon.start)?;
+    dict.set_item("end", icon.end)?;
+    dict.set_item("center_x", icon.center_x)?;
+    dict.set_item("center_y", icon.center_y)?;
+    Ok(dict)
+}
+
+/// Captcha image loaded from base64 or bytes, ready to be solved.
+#[pyclass(name = "IconCaptcha")]
+struct PyIconCaptcha {
+    inner: crate::IconCaptcha,
+}
+
+#[pymethods]
+impl PyIconCaptcha {
+    #[staticmethod]
+    fn load_from_base64(base64: &str) -> PyResult<Self> {
+        let inner = crate::IconCaptcha::load_from_base64(base64)?;
+        Ok(Self { inner })
+    }
+
+    #[staticmethod]
+    fn load_from_bytes(bytes: &[u8]) -> PyResult<Self> {
+        let inner = crate::IconCaptcha::load_from_bytes(bytes)?;
+        Ok(Self { inner })
+    }
+
+    /// Solve the captcha and return the Icon fields as a dict.
+    fn solve<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let icon = self.inner.solve_ref()?;
+        icon_to_dict(py, &icon)
+    }
+}
+
+/// Solve a base64 captcha image and return the Icon fields as a dict.
+#[pyfunction]
+fn solve<'py>(py: Python<'py>, base64: &str) -> PyResult<Bound<'py, PyDict>> {
+    let icon = crate::IconCaptcha::load_from_base64(base64)?.solve()?;
+    icon_to_dict(py, &icon)
+}
+
+#[pymodule]
+fn iconcaptcha_solver(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(solve, m)?)?;
+    m.add_class::<PyIconCaptcha>()?;
+    Ok(())
+}